@@ -1,4 +1,4 @@
-use clap::{ArgAction, Parser, crate_version};
+use clap::{ArgAction, Parser, ValueEnum, crate_version};
 use std::{ffi::OsStr, fmt::Debug};
 
 #[derive(Debug, Parser)]
@@ -41,15 +41,55 @@ METADATA EXAMPLE:
 // smoelius: Please keep the last four fields `args`, `operation`, `lib_sel`, and `output`, in that
 // order. Please keep all other fields sorted.
 struct Dylint {
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with("write_baseline"),
+        help = "Report only findings not recorded in the baseline at <PATH>, and fail only if \
+                there are such findings"
+    )]
+    baseline: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "REF",
+        help = "Report only diagnostics on lines that differ from git ref <REF>"
+    )]
+    diff_base: Option<String>,
+
     #[clap(long, help = "Automatically apply lint suggestions")]
     fix: bool,
 
+    #[clap(
+        long,
+        value_name = "N",
+        help = "Number of toolchains to check concurrently. Output is buffered and replayed in the \
+                same order as when checking sequentially. Cannot be greater than 1 with `--fix`."
+    )]
+    jobs: Option<usize>,
+
     #[clap(long, help = "Continue if `cargo check` fails")]
     keep_going: bool,
 
     #[clap(long, help = "Do not check other packages within the workspace")]
     no_deps: bool,
 
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Path to write the report to, rather than standard output"
+    )]
+    output_file: Option<String>,
+
+    #[clap(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "human",
+        help = "Format in which to report diagnostics"
+    )]
+    output_format: OutputFormat,
+
     #[clap(
         action = ArgAction::Append,
         number_of_values = 1,
@@ -63,6 +103,13 @@ struct Dylint {
     #[clap(long, help = "Check all packages in the workspace")]
     workspace: bool,
 
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "Record the current findings in a baseline at <PATH>"
+    )]
+    write_baseline: Option<String>,
+
     #[clap(last = true, help = "Arguments for `cargo check`")]
     args: Vec<String>,
 
@@ -78,6 +125,72 @@ struct Dylint {
 
 #[derive(Debug, Parser)]
 enum Operation {
+    #[clap(
+        about = "Remove build artifacts",
+        long_about = "Remove the directories in which Dylint builds libraries and checks packages.
+
+Each directory holds the artifacts for one toolchain. A library's directory may also hold the \
+artifacts of other libraries built in the same workspace.
+
+Use `--lib`, `--toolchain`, or `--unreferenced` to remove only some directories. Use `--dry-run` to \
+list the directories that would be removed."
+    )]
+    Clean {
+        #[clap(long, help = "List what would be removed without removing anything")]
+        dry_run: bool,
+
+        #[clap(
+            long,
+            value_name = "TOOLCHAIN",
+            help = "Remove only the artifacts for <TOOLCHAIN>"
+        )]
+        toolchain: Option<String>,
+
+        #[clap(
+            long,
+            help = "Remove only the artifacts for toolchains that no discovered library uses"
+        )]
+        unreferenced: bool,
+
+        #[clap(flatten)]
+        lib_sel: LibrarySelection,
+    },
+
+    #[clap(
+        about = "Describe or check library configuration",
+        long_about = "Use `--schema` to print a JSON Schema describing the configuration accepted \
+by the named libraries, i.e., the contents of `dylint.toml` and `[package.metadata.dylint]`.
+
+Use `--validate` to check the workspace's `dylint.toml` files and `[package.metadata.dylint]` \
+tables against that schema. Nothing is printed if no problems are found.
+
+Combine with `--all` to use all discovered libraries."
+    )]
+    Config {
+        #[clap(long, conflicts_with("validate"), help = "Print a JSON Schema")]
+        schema: bool,
+
+        #[clap(long, help = "Check configuration against the schema")]
+        validate: bool,
+
+        #[clap(flatten)]
+        lib_sel: LibrarySelection,
+    },
+
+    #[clap(
+        about = "Manage Dylint drivers",
+        long_about = "List, build, or remove the drivers in the driver directory, or export or \
+import a driver.
+
+An archive contains a driver built for a specific toolchain, together with the driver's version. \
+An imported driver is used only if its version and toolchain match those of the archive. \
+Otherwise, Dylint falls back to building a driver."
+    )]
+    Driver {
+        #[clap(subcommand)]
+        operation: DriverOperation,
+    },
+
     #[clap(
         about = "List libraries or lints",
         long_about = "If no libraries are named, list the name, toolchain, and location of all \
@@ -86,9 +199,20 @@ discovered libraries.
 If at least one library is named, list the name, level, and description of all lints in all named \
 libraries.
 
-Combine with `--all` to list all lints in all discovered libraries."
+Combine with `--all` to list all lints in all discovered libraries.
+
+Use `--format json` to produce machine-readable output."
     )]
     List {
+        #[clap(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            default_value = "text",
+            help = "Output format"
+        )]
+        format: ListFormat,
+
         #[clap(flatten)]
         lib_sel: LibrarySelection,
     },
@@ -105,6 +229,19 @@ Combine with `--all` to list all lints in all discovered libraries."
         path: String,
     },
 
+    #[clap(
+        about = "Update `dylint.lock`",
+        long_about = "Resolve the workspace's git-sourced libraries anew, and record the commits \
+they resolve to in `dylint.lock`.
+
+Without this command, Dylint uses the commits recorded in `dylint.lock`, so that every run loads \
+the same lint code."
+    )]
+    Update {
+        #[clap(flatten)]
+        lib_sel: LibrarySelection,
+    },
+
     #[clap(
         about = "Upgrade library package",
         long_about = "Upgrade the library package at <PATH> to the latest version of \
@@ -135,6 +272,86 @@ Combine with `--all` to list all lints in all discovered libraries."
     },
 }
 
+#[derive(Debug, Parser)]
+enum DriverOperation {
+    #[clap(
+        about = "Build a driver",
+        long_about = "Build the driver for <TOOLCHAIN> if it does not exist or is outdated, and \
+                      print its path"
+    )]
+    Build {
+        #[clap(
+            long,
+            value_name = "TOOLCHAIN",
+            help = "Toolchain of the driver to build"
+        )]
+        toolchain: String,
+    },
+
+    #[clap(about = "Remove all drivers")]
+    Clean,
+
+    #[clap(
+        about = "Export a driver to an archive",
+        long_about = "Write the driver for <TOOLCHAIN> to <ARCHIVE>, building the driver first if \
+                      necessary"
+    )]
+    Export {
+        #[clap(
+            long,
+            value_name = "TOOLCHAIN",
+            help = "Toolchain of the driver to export"
+        )]
+        toolchain: String,
+
+        #[clap(help = "Path of the archive to write")]
+        archive: String,
+    },
+
+    #[clap(
+        about = "Import a driver from an archive",
+        long_about = "Install the driver in <ARCHIVE>, e.g., one written by `cargo dylint driver \
+                      export`"
+    )]
+    Import {
+        #[clap(help = "Path of the archive to read")]
+        archive: String,
+    },
+
+    #[clap(
+        about = "List drivers",
+        long_about = "List the toolchain, version, and size of each driver"
+    )]
+    List,
+
+    #[clap(
+        about = "Remove unneeded drivers",
+        long_about = "Remove drivers for toolchains that are no longer installed, and drivers \
+                      for toolchains that no discovered library uses"
+    )]
+    Prune {
+        #[clap(flatten)]
+        lib_sel: Box<LibrarySelection>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    /// The usual `cargo check` output
+    Human,
+    /// One report for all toolchains, sorted by file and line, with duplicates removed
+    Combined,
+    /// The usual `cargo check` output, plus a SARIF 2.1.0 log
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ListFormat {
+    Text,
+    Json,
+}
+
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 #[cfg_attr(feature = "__clap_headings", clap(next_help_heading = Some("Library Selection")))]
 struct LibrarySelection {
@@ -148,6 +365,16 @@ struct LibrarySelection {
     )]
     branch: Option<String>,
 
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "exclude",
+        value_name = "PATTERN",
+        help = "Subdirectories of the `--git` or `--path` argument to exclude, along with any \
+                library packages within them"
+    )]
+    excludes: Vec<String>,
+
     #[clap(
         long,
         value_name = "URL",
@@ -177,6 +404,18 @@ struct LibrarySelection {
     )]
     libs: Vec<String>,
 
+    #[clap(
+        action = ArgAction::Append,
+        number_of_values = 1,
+        long = "lint",
+        value_name = "NAME",
+        help = "Lint to run. <NAME> can be a glob pattern. Only the libraries defining a matching \
+                lint are loaded, and their other lints are allowed. If no other libraries are \
+                selected, every discovered library is considered. Note that every library \
+                considered is built, so that its lints can be listed."
+    )]
+    lints: Vec<String>,
+
     #[clap(
         long,
         value_name = "PATH",
@@ -191,6 +430,13 @@ struct LibrarySelection {
     #[clap(long, help = "Ignore metadata entirely")]
     no_metadata: bool,
 
+    #[clap(
+        long,
+        help = "Pass `--offline` to every `cargo` command Dylint runs. Git libraries must already \
+                be in Cargo's cache. Also enabled by setting CARGO_NET_OFFLINE to `true`."
+    )]
+    offline: bool,
+
     #[clap(
         action = ArgAction::Append,
         number_of_values = 1,
@@ -244,14 +490,20 @@ struct OutputOptions {
 impl From<Dylint> for dylint::opts::Dylint {
     fn from(opts: Dylint) -> Self {
         let Dylint {
+            baseline,
+            diff_base,
             fix,
+            jobs,
             keep_going,
             no_deps,
+            output_file,
+            output_format,
             packages,
             workspace,
+            write_baseline,
             args,
             operation,
-            mut lib_sel,
+            lib_sel,
             output:
                 OutputOptions {
                     pipe_stderr,
@@ -263,34 +515,21 @@ impl From<Dylint> for dylint::opts::Dylint {
             None => dylint::opts::Operation::Check({
                 dylint::opts::Check {
                     lib_sel: lib_sel.into(),
+                    baseline,
+                    diff_base,
                     fix,
+                    jobs,
                     keep_going,
                     no_deps,
+                    output_file,
+                    output_format: output_format.into(),
                     packages,
                     workspace,
+                    write_baseline,
                     args,
                 }
             }),
-            Some(Operation::List { lib_sel: other }) => {
-                lib_sel.absorb(other);
-                dylint::opts::Operation::List(dylint::opts::List {
-                    lib_sel: lib_sel.into(),
-                })
-            }
-            Some(Operation::New { isolate, path }) => {
-                dylint::opts::Operation::New(dylint::opts::New { isolate, path })
-            }
-            Some(Operation::Upgrade {
-                allow_downgrade,
-                rust_version,
-                path,
-                auto_correct,
-            }) => dylint::opts::Operation::Upgrade(dylint::opts::Upgrade {
-                allow_downgrade,
-                rust_version,
-                auto_correct,
-                path,
-            }),
+            Some(operation) => subcommand_operation(operation, lib_sel),
         };
         Self {
             pipe_stderr,
@@ -301,6 +540,113 @@ impl From<Dylint> for dylint::opts::Dylint {
     }
 }
 
+fn subcommand_operation(
+    operation: Operation,
+    mut lib_sel: LibrarySelection,
+) -> dylint::opts::Operation {
+    match operation {
+        Operation::Clean {
+            dry_run,
+            toolchain,
+            unreferenced,
+            lib_sel: other,
+        } => {
+            lib_sel.absorb(other);
+            dylint::opts::Operation::Clean(dylint::opts::Clean {
+                lib_sel: lib_sel.into(),
+                dry_run,
+                toolchain,
+                unreferenced,
+            })
+        }
+        Operation::Config {
+            schema,
+            validate,
+            lib_sel: other,
+        } => {
+            lib_sel.absorb(other);
+            dylint::opts::Operation::Config(dylint::opts::Config {
+                lib_sel: lib_sel.into(),
+                schema,
+                validate,
+            })
+        }
+        Operation::Driver { operation } => dylint::opts::Operation::Driver(operation.into()),
+        Operation::List {
+            format,
+            lib_sel: other,
+        } => {
+            lib_sel.absorb(other);
+            dylint::opts::Operation::List(dylint::opts::List {
+                lib_sel: lib_sel.into(),
+                format: format.into(),
+            })
+        }
+        Operation::New { isolate, path } => {
+            dylint::opts::Operation::New(dylint::opts::New { isolate, path })
+        }
+        Operation::Update { lib_sel: other } => {
+            lib_sel.absorb(other);
+            dylint::opts::Operation::Update(dylint::opts::Update {
+                lib_sel: lib_sel.into(),
+            })
+        }
+        Operation::Upgrade {
+            allow_downgrade,
+            rust_version,
+            path,
+            auto_correct,
+        } => dylint::opts::Operation::Upgrade(dylint::opts::Upgrade {
+            allow_downgrade,
+            rust_version,
+            auto_correct,
+            path,
+        }),
+    }
+}
+
+impl From<DriverOperation> for dylint::opts::Driver {
+    fn from(operation: DriverOperation) -> Self {
+        match operation {
+            DriverOperation::Build { toolchain } => {
+                Self::Build(dylint::opts::DriverBuild { toolchain })
+            }
+            DriverOperation::Clean => Self::Clean,
+            DriverOperation::Export { toolchain, archive } => {
+                Self::Export(dylint::opts::DriverExport { toolchain, archive })
+            }
+            DriverOperation::Import { archive } => {
+                Self::Import(dylint::opts::DriverImport { archive })
+            }
+            DriverOperation::List => Self::List,
+            DriverOperation::Prune { lib_sel } => {
+                Self::Prune(Box::new(dylint::opts::DriverPrune {
+                    lib_sel: (*lib_sel).into(),
+                }))
+            }
+        }
+    }
+}
+
+impl From<OutputFormat> for dylint::opts::OutputFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Human => Self::Human,
+            OutputFormat::Combined => Self::Combined,
+            OutputFormat::Sarif => Self::Sarif,
+        }
+    }
+}
+
+impl From<ListFormat> for dylint::opts::ListFormat {
+    fn from(format: ListFormat) -> Self {
+        match format {
+            ListFormat::Text => Self::Text,
+            ListFormat::Json => Self::Json,
+        }
+    }
+}
+
 macro_rules! option_absorb {
     ($this:expr, $other:expr) => {
         if $other.is_some() {
@@ -319,12 +665,15 @@ impl LibrarySelection {
         let Self {
             all,
             branch,
+            excludes,
             git,
             lib_paths,
             libs,
+            lints,
             manifest_path,
             no_build,
             no_metadata,
+            offline,
             paths,
             pattern,
             rev,
@@ -332,12 +681,15 @@ impl LibrarySelection {
         } = other;
         self.all |= all;
         option_absorb!(&mut self.branch, branch);
+        self.excludes.extend(excludes);
         option_absorb!(&mut self.git, git);
         self.lib_paths.extend(lib_paths);
         self.libs.extend(libs);
+        self.lints.extend(lints);
         option_absorb!(&mut self.manifest_path, manifest_path);
         self.no_build |= no_build;
         self.no_metadata |= no_metadata;
+        self.offline |= offline;
         self.paths.extend(paths);
         option_absorb!(&mut self.pattern, pattern);
         option_absorb!(&mut self.rev, rev);
@@ -350,12 +702,15 @@ impl From<LibrarySelection> for dylint::opts::LibrarySelection {
         let LibrarySelection {
             all,
             branch,
+            excludes,
             git,
             lib_paths,
             libs,
+            lints,
             manifest_path,
             no_build,
             no_metadata,
+            offline,
             paths,
             pattern,
             rev,
@@ -364,12 +719,15 @@ impl From<LibrarySelection> for dylint::opts::LibrarySelection {
         Self {
             all,
             branch,
+            excludes,
             git,
             lib_paths,
             libs,
+            lints,
             manifest_path,
             no_build,
             no_metadata,
+            offline,
             paths,
             pattern,
             rev,
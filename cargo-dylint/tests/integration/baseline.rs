@@ -0,0 +1,80 @@
+use assert_cmd::{Command, cargo::cargo_bin_cmd};
+use predicates::prelude::*;
+use serde_json::{Value, json};
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+use tempfile::tempdir;
+
+#[test]
+fn baseline() {
+    let tempdir = tempdir().unwrap();
+    let baseline = tempdir.path().join("dylint-baseline.json");
+
+    base_command()
+        .arg("--write-baseline")
+        .arg(&baseline)
+        .assert()
+        .success();
+
+    let mut value = serde_json::from_str::<Value>(&read_to_string(&baseline).unwrap()).unwrap();
+    let findings = value["findings"].as_array().unwrap();
+    assert_eq!(1, findings.len());
+    assert_eq!(
+        Some("question_mark_in_expression"),
+        findings[0]["lint"].as_str()
+    );
+    assert_eq!(Some("b/src/lib.rs"), findings[0]["file"].as_str());
+    assert_eq!(Some("greet"), findings[0]["item_path"].as_str());
+
+    base_command()
+        .arg("--baseline")
+        .arg(&baseline)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("no longer occur").not());
+
+    // smoelius: Add an entry that does not occur.
+    let mut stale = findings[0].clone();
+    stale["item_path"] = json!("nonexistent");
+    value["findings"].as_array_mut().unwrap().push(stale);
+    write_json(&baseline, &value);
+
+    base_command()
+        .arg("--baseline")
+        .arg(&baseline)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "`question_mark_in_expression` in `b/src/lib.rs` (`nonexistent`)",
+        ));
+
+    // smoelius: With an empty baseline, the finding is new.
+    value["findings"] = json!([]);
+    write_json(&baseline, &value);
+
+    base_command()
+        .arg("--baseline")
+        .arg(&baseline)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Found 1 finding(s) not in the baseline",
+        ));
+}
+
+fn write_json(path: &Path, value: &Value) {
+    write(path, serde_json::to_string_pretty(value).unwrap()).unwrap();
+}
+
+fn base_command() -> Command {
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    let mut command = cargo_bin_cmd!("cargo-dylint");
+    command.current_dir("../fixtures/no_deps").args([
+        "dylint",
+        "--lib",
+        "question_mark_in_expression",
+    ]);
+    command
+}
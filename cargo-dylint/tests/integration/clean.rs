@@ -0,0 +1,83 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use dylint_internal::CommandExt;
+use predicates::prelude::*;
+use std::fs::{create_dir_all, write};
+use tempfile::{TempDir, tempdir};
+
+const TOOLCHAINS: [&str; 2] = [
+    "nightly-2024-01-01-x86_64-unknown-linux-gnu",
+    "nightly-2024-02-01-x86_64-unknown-linux-gnu",
+];
+
+#[test]
+fn dry_run() {
+    let tempdir = package();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "clean", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(TOOLCHAINS[0])
+                .and(predicate::str::contains(TOOLCHAINS[1]))
+                .and(predicate::str::contains("Would free 8 B")),
+        );
+
+    for toolchain in TOOLCHAINS {
+        assert!(target_dir(&tempdir, toolchain).exists());
+    }
+}
+
+#[test]
+fn toolchain() {
+    let tempdir = package();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "clean", "--toolchain", TOOLCHAINS[0]])
+        .assert()
+        .success();
+
+    assert!(!target_dir(&tempdir, TOOLCHAINS[0]).exists());
+    assert!(target_dir(&tempdir, TOOLCHAINS[1]).exists());
+}
+
+// smoelius: The package uses no libraries. So every toolchain is unreferenced.
+#[test]
+fn unreferenced() {
+    let tempdir = package();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "clean", "--unreferenced"])
+        .assert()
+        .success();
+
+    for toolchain in TOOLCHAINS {
+        assert!(!target_dir(&tempdir, toolchain).exists());
+    }
+}
+
+fn package() -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `clean_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "clean_test"])
+        .success()
+        .unwrap();
+
+    for toolchain in TOOLCHAINS {
+        let target_dir = target_dir(&tempdir, toolchain);
+        create_dir_all(target_dir.join("debug")).unwrap();
+        write(target_dir.join("debug/artifact"), "1234").unwrap();
+    }
+
+    tempdir
+}
+
+fn target_dir(tempdir: &TempDir, toolchain: &str) -> std::path::PathBuf {
+    tempdir.path().join("target/dylint/target").join(toolchain)
+}
@@ -0,0 +1,82 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use dylint_internal::CommandExt;
+use std::{
+    fs::{OpenOptions, write},
+    io::Write,
+    path::Path,
+};
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"pub fn greet() -> Result<(), std::str::Utf8Error> {
+    let unused = 0;
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    Ok(())
+}
+"#;
+
+// smoelius: `question_mark_in_expression` and `straggler` use different toolchains. So the package
+// is checked twice, and the `unused_variables` warning is emitted twice.
+#[test]
+fn combined() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `combined_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--lib", "--name", "combined_test"])
+        .success()
+        .unwrap();
+
+    write(tempdir.path().join("src/lib.rs"), SOURCE).unwrap();
+
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+
+    // smoelius: At most one library can be named with `--path`. So the libraries are listed in the
+    // package's metadata instead.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    write!(
+        file,
+        r#"
+[workspace.metadata.dylint]
+libraries = [
+    {{ path = "{}" }},
+    {{ path = "{}" }},
+]
+"#,
+        examples
+            .join("restriction/question_mark_in_expression")
+            .to_string_lossy()
+            .replace('\\', "\\\\"),
+        examples
+            .join("testing/straggler")
+            .to_string_lossy()
+            .replace('\\', "\\\\"),
+    )
+    .unwrap();
+
+    let assert = cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--output-format", "combined"])
+        .assert()
+        .success();
+
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+
+    assert_eq!(1, stdout.matches("unused variable: `unused`").count());
+    assert_eq!(
+        1,
+        stdout
+            .matches("[question_mark_in_expression] warning:")
+            .count()
+    );
+    assert!(
+        stdout.find("unused variable").unwrap()
+            < stdout.find("[question_mark_in_expression]").unwrap()
+    );
+    assert!(stdout.ends_with("2 warning(s) and 0 error(s) across all toolchains\n"));
+}
@@ -0,0 +1,90 @@
+use assert_cmd::{assert::Assert, cargo::cargo_bin_cmd};
+use dylint_internal::CommandExt;
+use predicates::prelude::*;
+use std::{fs::write, path::Path};
+use tempfile::{TempDir, tempdir};
+
+#[test]
+fn schema() {
+    let tempdir = package("");
+
+    dylint(&tempdir, "--schema").success().stdout(
+        predicate::str::contains(r#""unnamed_constant""#)
+            .and(predicate::str::contains(r#""threshold""#))
+            .and(predicate::str::contains(r#""lints""#)),
+    );
+}
+
+#[test]
+fn validate_ok() {
+    let tempdir = package(
+        "[unnamed_constant]
+threshold = 10
+
+[lints]
+unnamed_constant = \"deny\"
+",
+    );
+
+    dylint(&tempdir, "--validate").success();
+}
+
+#[test]
+fn validate_problems() {
+    let tempdir = package(
+        "[unamed_constant]
+threshold = 10
+
+[unnamed_constant]
+threshold = \"ten\"
+",
+    );
+
+    dylint(&tempdir, "--validate").failure().stderr(
+        predicate::str::contains("Found 2 problem(s) in Dylint configuration")
+            .and(predicate::str::contains(
+                "unknown key `unamed_constant`; it is not the name of a loaded library",
+            ))
+            .and(predicate::str::contains(
+                "`/unnamed_constant/threshold`: expected integer, found string",
+            )),
+    );
+}
+
+#[test]
+fn schema_and_validate() {
+    let tempdir = package("");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "config", "--schema", "--validate"])
+        .assert()
+        .failure();
+}
+
+fn package(dylint_toml: &str) -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `config_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--lib", "--name", "config_test"])
+        .success()
+        .unwrap();
+
+    write(tempdir.path().join("dylint.toml"), dylint_toml).unwrap();
+
+    tempdir
+}
+
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+fn dylint(tempdir: &TempDir, flag: &str) -> Assert {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/supplementary/unnamed_constant");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(tempdir)
+        .args(["dylint", "config", flag, "--path"])
+        .arg(path)
+        .assert()
+}
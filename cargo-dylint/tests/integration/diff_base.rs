@@ -0,0 +1,120 @@
+use assert_cmd::{Command, cargo::cargo_bin_cmd};
+use dylint_internal::{CommandExt, env};
+use predicates::prelude::*;
+use std::{
+    fs::{OpenOptions, write},
+    io::Write,
+    path::Path,
+};
+use tempfile::{TempDir, tempdir};
+
+const OLD_SOURCE: &str = r#"pub fn old() -> Result<(), std::str::Utf8Error> {
+    println!("{}", std::str::from_utf8(b"old")?);
+    Ok(())
+}
+"#;
+
+const NEW_SOURCE: &str = r#"
+pub fn new() -> Result<(), std::str::Utf8Error> {
+    println!("{}", std::str::from_utf8(b"new")?);
+    Ok(())
+}
+"#;
+
+#[test]
+fn diff_base() {
+    let tempdir = package();
+
+    write(
+        tempdir.path().join("src/lib.rs"),
+        format!("{OLD_SOURCE}{NEW_SOURCE}"),
+    )
+    .unwrap();
+
+    base_command(tempdir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("src/lib.rs:7:"))
+        .stderr(predicate::str::contains("src/lib.rs:2:").not());
+
+    // smoelius: A `deny` diagnostic on a changed line is still an error.
+    base_command(tempdir.path())
+        .env(env::RUSTFLAGS, "-D warnings")
+        .assert()
+        .failure();
+
+    // smoelius: But a `deny` diagnostic on an unchanged line is not.
+    git(tempdir.path(), &["commit", "--all", "--quiet", "-m", "New"]);
+
+    base_command(tempdir.path())
+        .env(env::RUSTFLAGS, "-D warnings")
+        .assert()
+        .success();
+}
+
+fn package() -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    let library = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../examples/restriction/question_mark_in_expression")
+        .canonicalize()
+        .unwrap();
+
+    dylint_internal::cargo::init("package `diff_base_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--lib", "--name", "diff_base_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[workspace.metadata.dylint]
+libraries = [{{ path = "{}" }}]
+"#,
+        library.to_string_lossy().replace('\\', "/")
+    )
+    .unwrap();
+
+    write(tempdir.path().join(".gitignore"), "/Cargo.lock\n/target\n").unwrap();
+    write(tempdir.path().join("src/lib.rs"), OLD_SOURCE).unwrap();
+
+    git(tempdir.path(), &["init", "--quiet"]);
+    git(tempdir.path(), &["add", "."]);
+    git(tempdir.path(), &["commit", "--quiet", "-m", "Old"]);
+
+    tempdir
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    std::process::Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=dylint",
+            "-c",
+            "user.email=dylint@example.com",
+        ])
+        .args(args)
+        .success()
+        .unwrap();
+}
+
+fn base_command(dir: &Path) -> Command {
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    let mut command = cargo_bin_cmd!("cargo-dylint");
+    command.current_dir(dir).args([
+        "dylint",
+        "--lib",
+        "question_mark_in_expression",
+        "--diff-base",
+        "HEAD",
+    ]);
+    command
+}
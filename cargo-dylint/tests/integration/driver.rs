@@ -0,0 +1,175 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use dylint_internal::{CommandExt, env, rustup::toolchain_path};
+use predicates::prelude::*;
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    path::Path,
+};
+use tempfile::tempdir;
+
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+#[test]
+fn export_import() {
+    let tempdir = tempdir().unwrap();
+
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/supplementary/unnamed_constant");
+    let toolchain_path = toolchain_path(&path).unwrap();
+    let toolchain = toolchain_path
+        .iter()
+        .next_back()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+
+    let exported = tempdir.path().join("exported");
+    let imported = tempdir.path().join("imported");
+    create_dir_all(&exported).unwrap();
+    create_dir_all(&imported).unwrap();
+
+    let archive = tempdir.path().join("driver.tar");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, &exported)
+        .args(["dylint", "driver", "export", "--toolchain", &toolchain])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, &imported)
+        .args(["dylint", "driver", "import"])
+        .arg(&archive)
+        .assert()
+        .success();
+
+    let metadata_json = imported.join(&toolchain).join("dylint-driver.json");
+    assert!(imported.join(&toolchain).join("dylint-driver").exists());
+    assert!(metadata_json.exists());
+
+    // smoelius: The imported driver matches. So no driver should be built.
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, &imported)
+        .args(["dylint", "list", "--path"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("driver for toolchain").not());
+
+    // smoelius: Once the metadata no longer matches, the driver should be rebuilt.
+    let contents = read_to_string(&metadata_json).unwrap();
+    write(
+        &metadata_json,
+        contents.replace(env!("CARGO_PKG_VERSION"), "0.0.0"),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, &imported)
+        .args(["dylint", "list", "--path"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("does not match its metadata")
+                .and(predicate::str::contains("driver for toolchain")),
+        );
+
+    assert!(!metadata_json.exists());
+}
+
+#[test]
+fn import_missing_metadata() {
+    let tempdir = tempdir().unwrap();
+
+    let archive = tempdir.path().join("driver.tar");
+    // smoelius: Two zero-filled blocks are an empty tar archive.
+    write(&archive, [0u8; 1024]).unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, tempdir.path())
+        .args(["dylint", "driver", "import"])
+        .arg(&archive)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "does not contain `dylint-driver.json`",
+        ));
+}
+
+#[test]
+fn prune_without_libraries() {
+    let tempdir = tempdir().unwrap();
+
+    let package = tempdir.path().join("package");
+    dylint_internal::cargo::init("package `prune_test`")
+        .build()
+        .args(["--name", "prune_test"])
+        .arg(&package)
+        .success()
+        .unwrap();
+
+    let driver_dir = tempdir.path().join("drivers/not-installed");
+    create_dir_all(&driver_dir).unwrap();
+    write(driver_dir.join("dylint-driver"), "").unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&package)
+        .env(env::DYLINT_DRIVER_PATH, tempdir.path().join("drivers"))
+        .args(["dylint", "driver", "prune"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No libraries were found. Refusing to prune",
+        ));
+
+    assert!(driver_dir.exists());
+}
+
+#[test]
+fn list_prune_clean() {
+    let tempdir = tempdir().unwrap();
+
+    write(tempdir.path().join("README.txt"), "").unwrap();
+    for toolchain in ["not-installed-a", "not-installed-b"] {
+        let driver_dir = tempdir.path().join(toolchain);
+        create_dir_all(&driver_dir).unwrap();
+        write(driver_dir.join("dylint-driver"), "").unwrap();
+    }
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, tempdir.path())
+        .args(["dylint", "driver", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"(?m)^not-installed-a +<unknown> +0 B +\(outdated\)$")
+                .unwrap()
+                .and(predicate::str::contains("not-installed-b")),
+        );
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, tempdir.path())
+        .args(["dylint", "driver", "prune"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Removed driver for toolchain `not-installed-a` (toolchain is not installed)",
+        ));
+
+    assert!(!tempdir.path().join("not-installed-a").exists());
+    assert!(!tempdir.path().join("not-installed-b").exists());
+
+    let driver_dir = tempdir.path().join("not-installed-c");
+    create_dir_all(&driver_dir).unwrap();
+    write(driver_dir.join("dylint-driver"), "").unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::DYLINT_DRIVER_PATH, tempdir.path())
+        .args(["dylint", "driver", "clean"])
+        .assert()
+        .success();
+
+    assert!(!driver_dir.exists());
+    assert!(tempdir.path().join("README.txt").exists());
+}
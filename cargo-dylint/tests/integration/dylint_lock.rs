@@ -0,0 +1,76 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use dylint_internal::CommandExt;
+use predicates::prelude::*;
+use regex::Regex;
+use std::{
+    fs::{OpenOptions, read_to_string, write},
+    io::Write,
+};
+use tempfile::tempdir;
+
+// smoelius: "Separate lints into categories" commit
+const REV: &str = "402fc24351c60a3c474e786fd76aa66aa8638d55";
+
+#[test]
+fn dylint_lock() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `dylint_lock_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "dylint_lock_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+git = "https://github.com/trailofbits/dylint"
+pattern = "examples/general/crate_wide_allow"
+"#,
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .success();
+
+    let dylint_lock = tempdir.path().join("dylint.lock");
+    let contents = read_to_string(&dylint_lock).unwrap();
+    assert!(contents.contains(r#"git = "https://github.com/trailofbits/dylint""#));
+    assert!(contents.contains(r#"name = "crate_wide_allow""#));
+
+    let re = Regex::new(r#"commit = "([0-9a-f]{40})""#).unwrap();
+    let commit = re.captures(&contents).unwrap()[1].to_owned();
+
+    // smoelius: Point the lock at an older commit. The older commit's package has a different
+    // version, so the lock must be honored (and then found to be inconsistent).
+    write(&dylint_lock, contents.replace(&commit, REV)).unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "at commit {REV} no longer matches `dylint.lock`"
+        )));
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "update"])
+        .assert()
+        .success();
+
+    let contents = read_to_string(&dylint_lock).unwrap();
+    assert!(!contents.contains(REV));
+    assert!(re.is_match(&contents));
+}
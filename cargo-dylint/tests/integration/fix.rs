@@ -1,5 +1,6 @@
 use anyhow::{Context, Result, anyhow};
 use assert_cmd::{cargo::cargo_bin_cmd, prelude::*};
+use predicates::prelude::*;
 use std::{
     fs::{OpenOptions, read_to_string, write},
     io::Write,
@@ -60,6 +61,20 @@ fn fix() {
     assert_eq!(MAIN_FIXED, main_actual);
 }
 
+#[test]
+fn fix_with_jobs() {
+    let tempdir = tempdir().unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "--all", "--fix", "--jobs", "2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`--fix` cannot be used with `--jobs` greater than 1",
+        ));
+}
+
 fn append_workspace_metadata(path: &Path) -> Result<()> {
     let manifest = path.join("Cargo.toml");
     let mut file = OpenOptions::new()
@@ -1,7 +1,12 @@
 use assert_cmd::{cargo::cargo_bin_cmd, prelude::*};
 use dylint_internal::{CommandExt, env, packaging::isolate};
 use predicates::prelude::*;
-use std::{env::remove_var, fs::OpenOptions, io::Write};
+use std::{
+    env::remove_var,
+    fs::{OpenOptions, copy, create_dir_all, read_to_string, write},
+    io::Write,
+    path::Path,
+};
 use tempfile::tempdir;
 
 // smoelius: "Separate lints into categories" commit
@@ -28,6 +33,96 @@ fn array_pattern() {
     assert!(lines[1].starts_with("question_mark_in_expression "));
 }
 
+/// Verify that a warm run uses the discovery cache rather than running `cargo metadata` and `rustup
+/// show active-toolchain` on the library package.
+#[cfg(unix)]
+#[test]
+fn discovery_cache() {
+    use std::{
+        fs::{Permissions, create_dir, set_permissions},
+        os::unix::fs::PermissionsExt,
+    };
+
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `discovery_cache_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "discovery_cache_test"])
+        .success()
+        .unwrap();
+
+    isolate(tempdir.path()).unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+path = "{}/../examples/general/crate_wide_allow"
+"#,
+        env!("CARGO_MANIFEST_DIR").replace('\\', "\\\\")
+    )
+    .unwrap();
+
+    let discovery_json = tempdir.path().join("target/dylint/discovery.json");
+
+    assert!(!discovery_json.exists());
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .success();
+
+    let contents = read_to_string(&discovery_json).unwrap();
+    assert!(contents.contains(r#""lib_name": "crate_wide_allow""#));
+
+    // smoelius: On the warm run, `cargo metadata` and `rustup show active-toolchain` fail if they
+    // are run on the library package. Other invocations are passed through.
+    let shims = tempdir.path().join("shims");
+    create_dir(&shims).unwrap();
+    for (name, condition) in [
+        (
+            "cargo",
+            r#"[ "$1" = metadata ] && [ "$(basename "$(/bin/pwd)")" = crate_wide_allow ]"#,
+        ),
+        ("rustup", r#"[ "$1 $2" = "show active-toolchain" ]"#),
+    ] {
+        let shim = shims.join(name);
+        write(
+            &shim,
+            format!(
+                r#"#!/bin/sh
+if {condition}; then
+    echo "unexpected \`{name} $*\`" >&2
+    exit 1
+fi
+export PATH="$REAL_PATH"
+exec {name} "$@"
+"#
+            ),
+        )
+        .unwrap();
+        set_permissions(&shim, Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let path = std::env::var("PATH").unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .env("PATH", format!("{}:{path}", shims.display()))
+        .env("REAL_PATH", &path)
+        .args(["dylint", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("crate_wide_allow"));
+}
+
 #[cfg(unix)]
 #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
 #[test]
@@ -344,6 +439,179 @@ path = "{}/../examples/general/nonexistent_library"
         .stderr(predicate::str::contains("No library packages found in"));
 }
 
+// smoelius: `dylint_linting` is not a library package. But it is a registry package that Dylint
+// must download before determining that.
+#[test]
+fn registry_library() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `registry_library_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "registry_library_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+version = "4"
+"#
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "A registry library must name its package with a `package` entry",
+        ));
+
+    writeln!(file, r#"package = "dylint_linting""#).unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::is_match(
+                r"Could not find `cdylib` target for package `[^`]*dylint_linting",
+            )
+            .unwrap(),
+        );
+}
+
+/// Verify that the `exclude` key excludes both library packages and directories containing them.
+#[test]
+fn exclude() {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `exclude_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "exclude_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+path = "{}/../examples"
+pattern = ["general/c*", "restriction/c*"]
+exclude = ["general", "restriction/const*"]
+"#,
+        env!("CARGO_MANIFEST_DIR").replace('\\', "\\\\")
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("collapsible_unwrap")
+                .and(predicate::str::contains("const_path_join").not())
+                .and(predicate::str::contains("crate_wide_allow").not()),
+        );
+}
+
+// smoelius: crates.io is replaced with a directory source containing one library package. The
+// package's `.cargo-checksum.json` lists no files, so Cargo does not verify any.
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+#[test]
+fn registry_library_from_replaced_source() {
+    let tempdir = tempdir().unwrap();
+
+    let vendor = tempdir.path().join("vendor");
+    let library = vendor.join("registry_library-0.1.0");
+    create_dir_all(library.join("src")).unwrap();
+    write(
+        library.join("Cargo.toml"),
+        r#"[package]
+name = "registry_library"
+version = "0.1.0"
+edition = "2024"
+
+[lib]
+crate-type = ["cdylib"]
+"#,
+    )
+    .unwrap();
+    write(library.join("src/lib.rs"), "").unwrap();
+    copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/general/rust-toolchain"),
+        library.join("rust-toolchain"),
+    )
+    .unwrap();
+    write(library.join(".cargo-checksum.json"), r#"{"files":{}}"#).unwrap();
+
+    let package = tempdir.path().join("package");
+    create_dir_all(&package).unwrap();
+
+    dylint_internal::cargo::init("package `registry_library_test`")
+        .build()
+        .current_dir(&package)
+        .args(["--name", "registry_library_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(package.join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+package = "registry_library"
+version = "0.1"
+"#
+    )
+    .unwrap();
+
+    create_dir_all(package.join(".cargo")).unwrap();
+    write(
+        package.join(".cargo/config.toml"),
+        format!(
+            r#"[source.crates-io]
+replace-with = "vendored"
+
+[source.vendored]
+directory = "{}"
+"#,
+            vendor.to_string_lossy().replace('\\', "\\\\")
+        ),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&package)
+        .args(["dylint", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"(?m)^registry_library +nightly-2025-09-18[^ ]* +.*vendor")
+                .unwrap(),
+        );
+}
+
 /// Verify that changes to `RUSTFLAGS` do not cause workspace metadata entries to be rebuilt.
 #[test]
 fn rustflags_change() {
@@ -421,7 +689,7 @@ pattern = "examples/general/crate_wide_allow"
         .assert()
         .success();
 
-    writeln!(file, r#"revision = "{REV}""#,).unwrap();
+    writeln!(file, r#"revision = "{REV}""#).unwrap();
 
     cargo_bin_cmd!("cargo-dylint")
         .current_dir(&tempdir)
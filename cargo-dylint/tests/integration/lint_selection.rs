@@ -0,0 +1,136 @@
+use assert_cmd::{assert::Assert, cargo::cargo_bin_cmd};
+use dylint_internal::CommandExt;
+use predicates::prelude::*;
+use std::{
+    fs::{OpenOptions, write},
+    io::Write,
+    path::Path,
+};
+use tempfile::{TempDir, tempdir};
+
+const SOURCE: &str = r#"pub fn greet() -> Result<(), std::str::Utf8Error> {
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    let _ = std::env::var("RUSTFLAGS");
+    Ok(())
+}
+"#;
+
+const QUESTION_MARK_IN_EXPRESSION: &str = "using the `?` operator within an expression";
+const ENV_LITERAL: &str = "referring to an environment variable with a string literal";
+
+#[test]
+fn sanity() {
+    let tempdir = package(SOURCE);
+
+    dylint(&tempdir, &["--all"]).success().stderr(
+        predicate::str::contains(QUESTION_MARK_IN_EXPRESSION)
+            .and(predicate::str::contains(ENV_LITERAL)),
+    );
+}
+
+#[test]
+fn lint() {
+    let tempdir = package(SOURCE);
+
+    dylint(&tempdir, &["--lint", "question_mark_in_expression"])
+        .success()
+        .stderr(
+            predicate::str::contains(QUESTION_MARK_IN_EXPRESSION)
+                .and(predicate::str::contains(ENV_LITERAL).not()),
+        );
+}
+
+#[test]
+fn lint_pattern() {
+    let tempdir = package(SOURCE);
+
+    dylint(&tempdir, &["--lint", "env-*"]).success().stderr(
+        predicate::str::contains(ENV_LITERAL)
+            .and(predicate::str::contains(QUESTION_MARK_IN_EXPRESSION).not()),
+    );
+}
+
+#[test]
+fn unknown_lint() {
+    let tempdir = package(SOURCE);
+
+    dylint(&tempdir, &["--lint", "nonexistent_lint"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "No loaded library defines a lint matching `--lint nonexistent_lint`",
+        ));
+}
+
+// smoelius: `crate_wide_allow` is defined by both `general` and `crate_wide_allow`. Loading both
+// libraries with the same toolchain would register the lint twice.
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+#[test]
+fn lint_in_composite_and_single_lint_library() {
+    let tempdir = package("#![allow(dead_code)]\n");
+
+    let general = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/general");
+    let crate_wide_allow = general.join("crate_wide_allow");
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[workspace.metadata.dylint]
+libraries = [
+    {{ path = "{}" }},
+    {{ path = "{}" }},
+]
+"#,
+        general.to_string_lossy().replace('\\', "\\\\"),
+        crate_wide_allow.to_string_lossy().replace('\\', "\\\\"),
+    )
+    .unwrap();
+
+    let assert = cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "--lint", "crate-wide-allow"])
+        .assert()
+        .success();
+
+    let stderr = std::str::from_utf8(&assert.get_output().stderr).unwrap();
+    assert_eq!(
+        1,
+        stderr
+            .matches("warning: silently overrides `--warn dead-code`")
+            .count(),
+        "{stderr}"
+    );
+}
+
+fn package(source: &str) -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `lint_selection_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--lib", "--name", "lint_selection_test"])
+        .success()
+        .unwrap();
+
+    write(tempdir.path().join("src/lib.rs"), source).unwrap();
+
+    tempdir
+}
+
+// smoelius: The pattern matches `env_literal` and `question_mark_in_expression`.
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+fn dylint(tempdir: &TempDir, args: &[&str]) -> Assert {
+    let restriction = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/restriction");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(tempdir)
+        .args(["dylint", "--path"])
+        .arg(restriction)
+        .args(["--pattern", "[eq]*"])
+        .args(args)
+        .assert()
+}
@@ -0,0 +1,105 @@
+use assert_cmd::{assert::Assert, cargo::cargo_bin_cmd};
+use dylint_internal::{CommandExt, env};
+use predicates::prelude::*;
+use std::{fs::write, path::Path};
+use tempfile::{TempDir, tempdir};
+
+const SOURCE: &str = r#"pub fn greet() -> Result<(), std::str::Utf8Error> {
+    println!("{}", std::str::from_utf8(b"Hello, world!")?);
+    Ok(())
+}
+"#;
+
+#[test]
+fn deny() {
+    let tempdir = package(
+        r#"[lints]
+question_mark_in_expression = "deny"
+"#,
+    );
+
+    dylint(&tempdir).failure().stderr(predicate::str::contains(
+        "error: using the `?` operator within an expression",
+    ));
+}
+
+#[test]
+fn priority() {
+    let tempdir = package(
+        r#"[lints]
+question_mark_in_expression = { level = "allow", priority = 1 }
+warnings = "deny"
+"#,
+    );
+
+    dylint(&tempdir)
+        .success()
+        .stderr(predicate::str::contains("question_mark_in_expression").not());
+}
+
+#[test]
+fn unknown_lint() {
+    let tempdir = package(
+        r#"[lints]
+nonexistent_lint = "warn"
+"#,
+    );
+
+    dylint(&tempdir).failure().stderr(predicate::str::contains(
+        "`dylint.toml` sets the level of unknown lint `nonexistent_lint` (loaded libraries: \
+         `question_mark_in_expression`)",
+    ));
+}
+
+/// Verify that two libraries can add lints to the same group, and that the group can be used on the
+/// command line.
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+#[test]
+fn group_from_two_libraries() {
+    let tempdir = package("");
+    write(tempdir.path().join("src/lib.rs"), "#![allow(dead_code)]\n").unwrap();
+
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .env(env::DYLINT_RUSTFLAGS, "--warn dylint_general")
+        .args(["dylint", "--path"])
+        .arg(examples.join("general/abs_home_path"))
+        .arg("--path")
+        .arg(examples.join("general/crate_wide_allow"))
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("warning: silently overrides `--warn dead-code`").and(
+                predicate::str::contains("`-W crate-wide-allow` implied by `-W dylint-general`"),
+            ),
+        );
+}
+
+fn package(dylint_toml: &str) -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `lints_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--lib", "--name", "lints_test"])
+        .success()
+        .unwrap();
+
+    write(tempdir.path().join("src/lib.rs"), SOURCE).unwrap();
+    write(tempdir.path().join("dylint.toml"), dylint_toml).unwrap();
+
+    tempdir
+}
+
+#[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+fn dylint(tempdir: &TempDir) -> Assert {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples");
+
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(tempdir)
+        .args(["dylint", "--path"])
+        .arg(examples.join("restriction/question_mark_in_expression"))
+        .assert()
+}
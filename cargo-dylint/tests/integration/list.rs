@@ -16,6 +16,7 @@ use glob::glob;
 use predicates::prelude::*;
 use std::{
     env::join_paths,
+    fs::copy,
     path::{Path, PathBuf},
 };
 use tempfile::tempdir;
@@ -73,6 +74,64 @@ fn one_name_multiple_toolchains() {
         );
 }
 
+// smoelius: Rename a library built with one toolchain so that it appears to have been built with
+// another. The driver for the other toolchain should refuse to load it.
+#[test]
+fn rustc_mismatch() {
+    let tempdir = tempdir().unwrap();
+
+    new_template(tempdir.path()).unwrap();
+
+    patch_dylint_template(
+        tempdir.path(),
+        msrv::MSRV_CHANNEL,
+        msrv::MSRV_CLIPPY_UTILS_REV,
+    )
+    .unwrap();
+    dylint_internal::cargo::build(&format!(
+        "dylint-template with channel `{}`",
+        msrv::MSRV_CHANNEL
+    ))
+    .build()
+    .sanitize_environment()
+    .current_dir(&tempdir)
+    .success()
+    .unwrap();
+
+    let path = glob(
+        &target_debug(tempdir.path())
+            .unwrap()
+            .join(library_filename("fill_me_in", "*"))
+            .to_string_lossy(),
+    )
+    .ok()
+    .as_mut()
+    .and_then(Iterator::next)
+    .unwrap()
+    .unwrap();
+
+    let file_name = path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .replace(msrv::MSRV_CHANNEL, msrv::MSRV_PLUS_1_CHANNEL);
+    let renamed = path.with_file_name(file_name);
+    copy(&path, &renamed).unwrap();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .args(["dylint", "list", "--lib-path", &renamed.to_string_lossy()])
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains(format!("(toolchain `{}", msrv::MSRV_CHANNEL)).and(
+                predicate::str::contains(format!(
+                    "rebuild the library with toolchain `{}",
+                    msrv::MSRV_PLUS_1_CHANNEL
+                )),
+            ),
+        );
+}
+
 fn patch_dylint_template(path: &Path, channel: &str, clippy_utils_rev: &str) -> Result<()> {
     set_toolchain_channel(path, channel)?;
     set_clippy_utils_dependency_revision(path, clippy_utils_rev)?;
@@ -163,6 +222,75 @@ fn opts_library_package() {
         );
 }
 
+#[test]
+fn list_json() {
+    let assert = cargo_bin_cmd!("cargo-dylint")
+        .args([
+            "dylint",
+            "list",
+            "--format",
+            "json",
+            "--path",
+            "../examples/general/crate_wide_allow",
+        ])
+        .assert()
+        .success();
+
+    let value: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+    let [library] = value.as_array().unwrap().as_slice() else {
+        panic!("expected exactly one library: {value:#?}");
+    };
+    assert_eq!("crate_wide_allow", library["name"]);
+    assert_eq!(Some(true), library["built"].as_bool());
+
+    let [lint] = library["lints"].as_array().unwrap().as_slice() else {
+        panic!("expected exactly one lint: {library:#?}");
+    };
+    assert_eq!("crate_wide_allow", lint["name"]);
+    assert_eq!("warn", lint["level"]);
+    assert_eq!("early", lint["pass_kind"]);
+    assert_eq!(serde_json::json!(["dylint_general"]), lint["groups"]);
+}
+
+#[test]
+fn list_groups() {
+    cargo_bin_cmd!("cargo-dylint")
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general/crate_wide_allow",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"\n    dylint_general +group +crate_wide_allow\n").unwrap(),
+        );
+}
+
+#[test]
+fn list_exclude() {
+    cargo_bin_cmd!("cargo-dylint")
+        .args([
+            "dylint",
+            "list",
+            "--path",
+            "../examples/general",
+            "--pattern",
+            "[ac]*",
+            "--exclude",
+            "a*",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("crate_wide_allow")
+                .and(predicate::str::contains("abs_home_path").not())
+                .and(predicate::str::contains("await_holding_span_guard").not()),
+        );
+}
+
 #[test]
 fn relative_path() {
     let tempdir = tempdir().unwrap();
@@ -1,12 +1,24 @@
 #![cfg_attr(dylint_lib = "general", allow(crate_wide_allow))]
 #![cfg_attr(dylint_lib = "supplementary", allow(nonexistent_path_in_comment))]
 
+mod baseline;
+mod clean;
+mod combined;
+mod config;
 mod depinfo_dylint_libs;
+mod diff_base;
+mod driver;
 mod dylint_driver_path;
+mod dylint_lock;
 mod fix;
 mod library_packages;
+mod lint_selection;
+mod lints;
 mod list;
 mod nightly_toolchain;
 mod no_deps;
+mod offline;
+mod package_config;
 mod package_options;
+mod sarif;
 mod warn;
@@ -0,0 +1,70 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use dylint_internal::{CommandExt, env};
+use predicates::prelude::*;
+use std::{fs::OpenOptions, io::Write};
+use tempfile::{TempDir, tempdir};
+
+const URL: &str = "https://github.com/trailofbits/dylint";
+
+// smoelius: `CARGO_HOME` is set to an empty directory. So the library cannot be in Cargo's cache.
+
+#[test]
+fn offline_flag() {
+    let cargo_home = tempdir().unwrap();
+    let package = package();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::CARGO_HOME, cargo_home.path())
+        .current_dir(&package)
+        .args(["dylint", "list", "--offline"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Git library `{URL}` is not in Cargo's cache"
+        )));
+}
+
+#[test]
+fn cargo_net_offline() {
+    let cargo_home = tempdir().unwrap();
+    let package = package();
+
+    cargo_bin_cmd!("cargo-dylint")
+        .env(env::CARGO_HOME, cargo_home.path())
+        .env(env::CARGO_NET_OFFLINE, "true")
+        .current_dir(&package)
+        .args(["dylint", "list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Git library `{URL}` is not in Cargo's cache"
+        )));
+}
+
+fn package() -> TempDir {
+    let tempdir = tempdir().unwrap();
+
+    dylint_internal::cargo::init("package `offline_test`")
+        .build()
+        .current_dir(&tempdir)
+        .args(["--name", "offline_test"])
+        .success()
+        .unwrap();
+
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(tempdir.path().join("Cargo.toml"))
+        .unwrap();
+
+    write!(
+        file,
+        r#"
+[[workspace.metadata.dylint.libraries]]
+git = "{URL}"
+pattern = "examples/general/crate_wide_allow"
+"#,
+    )
+    .unwrap();
+
+    tempdir
+}
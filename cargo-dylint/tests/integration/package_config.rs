@@ -0,0 +1,93 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use std::{
+    fs::{create_dir_all, write},
+    path::Path,
+};
+use tempfile::tempdir;
+
+const SOURCE: &str = "pub fn scale(x: u64) -> u64 {
+    x * 50
+}
+";
+
+// smoelius: The workspace's `dylint.toml` file sets `unnamed_constant`'s threshold above 50.
+// - `a`'s `dylint.toml` file lowers the threshold, so the constant is flagged in `a`.
+// - `b`'s `dylint.toml` file lowers the threshold, but `b`'s `[package.metadata.dylint]` table
+//   raises it again, so the constant is not flagged in `b`.
+// - `c` has no configuration of its own, so the constant is not flagged in `c`.
+#[test]
+fn package_config() {
+    let tempdir = tempdir().unwrap();
+
+    write(
+        tempdir.path().join("Cargo.toml"),
+        r#"[workspace]
+members = ["a", "b", "c"]
+resolver = "3"
+"#,
+    )
+    .unwrap();
+    write(
+        tempdir.path().join("dylint.toml"),
+        "[unnamed_constant]
+threshold = 100
+",
+    )
+    .unwrap();
+
+    for (name, metadata) in [
+        ("a", ""),
+        (
+            "b",
+            "
+[package.metadata.dylint.unnamed_constant]
+threshold = 100
+",
+        ),
+        ("c", ""),
+    ] {
+        let dir = tempdir.path().join(name);
+        create_dir_all(dir.join("src")).unwrap();
+        write(
+            dir.join("Cargo.toml"),
+            format!(
+                r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2024"
+publish = false
+{metadata}"#
+            ),
+        )
+        .unwrap();
+        write(dir.join("src/lib.rs"), SOURCE).unwrap();
+    }
+
+    for name in ["a", "b"] {
+        write(
+            tempdir.path().join(name).join("dylint.toml"),
+            "[unnamed_constant]
+threshold = 10
+",
+        )
+        .unwrap();
+    }
+
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/supplementary/unnamed_constant");
+
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir(&tempdir)
+        .args(["dylint", "--path"])
+        .arg(path)
+        .arg("--workspace")
+        .assert()
+        .success()
+        .stderr(
+            predicate::str::contains("a/src/lib.rs")
+                .and(predicate::str::contains("b/src/lib.rs").not())
+                .and(predicate::str::contains("c/src/lib.rs").not()),
+        );
+}
@@ -0,0 +1,66 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+use std::fs::read_to_string;
+use tempfile::tempdir;
+
+#[test]
+fn sarif() {
+    let tempdir = tempdir().unwrap();
+    let output_file = tempdir.path().join("results.sarif");
+
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir("../fixtures/no_deps")
+        .args([
+            "dylint",
+            "--lib",
+            "question_mark_in_expression",
+            "--output-format",
+            "sarif",
+            "--output-file",
+        ])
+        .arg(&output_file)
+        .assert()
+        .success();
+
+    let log = serde_json::from_str::<Value>(&read_to_string(output_file).unwrap()).unwrap();
+
+    assert_eq!(Some("2.1.0"), log["version"].as_str());
+
+    let run = &log["runs"][0];
+
+    let extensions = run["tool"]["extensions"].as_array().unwrap();
+    assert_eq!(1, extensions.len());
+    assert_eq!(
+        Some("question_mark_in_expression"),
+        extensions[0]["name"].as_str()
+    );
+    assert!(extensions[0]["properties"]["toolchain"].is_string());
+    assert_eq!(
+        Some("question_mark_in_expression"),
+        extensions[0]["rules"][0]["id"].as_str()
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert!(results.iter().any(|result| {
+        result["ruleId"].as_str() == Some("question_mark_in_expression")
+            && result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].as_str()
+                == Some("b/src/lib.rs")
+    }));
+}
+
+#[test]
+fn output_file_requires_output_format() {
+    #[cfg_attr(dylint_lib = "general", allow(abs_home_path))]
+    cargo_bin_cmd!("cargo-dylint")
+        .current_dir("../fixtures/no_deps")
+        .args([
+            "dylint",
+            "--lib",
+            "question_mark_in_expression",
+            "--output-file",
+            "results.sarif",
+        ])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,25 @@
+use anyhow::{Context, Result, ensure};
+use dylint_internal::env;
+use std::process::Command;
+
+// smoelius: The driver compares these against the values that `dylint_library!` exports from each
+// library before calling into the library.
+pub fn build() -> Result<()> {
+    let rustc = env::var(env::RUSTC)?;
+    let output = Command::new(&rustc)
+        .arg("-vV")
+        .output()
+        .with_context(|| format!("Could not run `{rustc} -vV`"))?;
+    ensure!(output.status.success(), "`{rustc} -vV` failed");
+    let stdout = String::from_utf8(output.stdout)?;
+    let commit_hash = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("commit-hash: "))
+        .unwrap_or("unknown");
+    let toolchain = env::var(env::RUSTUP_TOOLCHAIN).unwrap_or_default();
+
+    println!("cargo:rustc-env=DYLINT_RUSTC_COMMIT_HASH={commit_hash}");
+    println!("cargo:rustc-env=DYLINT_RUSTUP_TOOLCHAIN={toolchain}");
+
+    Ok(())
+}
@@ -12,18 +12,37 @@ extern crate rustc_lint;
 extern crate rustc_session;
 extern crate rustc_span;
 
-use anyhow::{Result, bail, ensure};
-use dylint_internal::{env, parse_path_filename, rustup::is_rustc};
+use anyhow::{Result, anyhow, bail, ensure};
+use dylint_internal::{
+    env,
+    list::{self, PassKind},
+    parse_path_filename,
+    rustup::is_rustc,
+};
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     ffi::{CString, OsStr},
     path::{Path, PathBuf},
 };
 
 pub const DYLINT_VERSION: &str = "0.1.0";
 
+const RUSTC_COMMIT_HASH: &str = env!("DYLINT_RUSTC_COMMIT_HASH");
+
+const RUSTUP_TOOLCHAIN: &str = env!("DYLINT_RUSTUP_TOOLCHAIN");
+
+const UNKNOWN: &str = "unknown";
+
 type DylintVersionFunc = unsafe fn() -> *mut std::os::raw::c_char;
 
+type DylintRustcCommitHashFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
+type DylintToolchainFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
+type DylintConfigSchemasFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
+type DylintLintGroupsFunc = unsafe extern "C" fn() -> *mut std::os::raw::c_char;
+
 type RegisterLintsFunc =
     unsafe fn(sess: &rustc_session::Session, store: &mut rustc_lint::LintStore);
 
@@ -86,6 +105,85 @@ impl LoadedLibrary {
             session_err(sess, &err);
         });
     }
+
+    // smoelius: `dylint_rustc_commit_hash` and `dylint_toolchain` are optional. Libraries built
+    // with older versions of `dylint_linting` do not export them. Those libraries are loaded
+    // without the check, as before.
+    fn check_rustc(&self) -> Result<()> {
+        let Ok(func) = (unsafe {
+            self.lib
+                .get::<DylintRustcCommitHashFunc>(b"dylint_rustc_commit_hash")
+        }) else {
+            return Ok(());
+        };
+        let commit_hash = unsafe { CString::from_raw(func()) }.into_string()?;
+        if commit_hash == RUSTC_COMMIT_HASH
+            || commit_hash == UNKNOWN
+            || RUSTC_COMMIT_HASH == UNKNOWN
+        {
+            return Ok(());
+        }
+        let toolchain = match unsafe { self.lib.get::<DylintToolchainFunc>(b"dylint_toolchain") } {
+            Ok(func) => unsafe { CString::from_raw(func()) }.into_string()?,
+            Err(_) => String::new(),
+        };
+        bail!(rustc_mismatch_message(&self.path, &commit_hash, &toolchain))
+    }
+
+    // smoelius: `dylint_config_schemas` is optional. Libraries built with older versions of
+    // `dylint_linting`, or that are not configurable, do not export it.
+    fn config_schemas(&self) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let Ok(func) = (unsafe {
+            self.lib
+                .get::<DylintConfigSchemasFunc>(b"dylint_config_schemas")
+        }) else {
+            return Ok(serde_json::Map::new());
+        };
+        let json = unsafe { CString::from_raw(func()) }.into_string()?;
+        serde_json::from_str(&json).map_err(|err| {
+            anyhow!(
+                "could not parse config schemas of `{}`: {err}",
+                self.path.to_string_lossy()
+            )
+        })
+    }
+
+    // smoelius: Like `dylint_config_schemas`, `dylint_lint_groups` is optional. Libraries built
+    // with older versions of `dylint_linting` do not export it.
+    fn lint_groups(&self) -> Result<BTreeMap<String, Vec<String>>> {
+        let Ok(func) = (unsafe { self.lib.get::<DylintLintGroupsFunc>(b"dylint_lint_groups") })
+        else {
+            return Ok(BTreeMap::new());
+        };
+        let json = unsafe { CString::from_raw(func()) }.into_string()?;
+        serde_json::from_str(&json).map_err(|err| {
+            anyhow!(
+                "could not parse lint groups of `{}`: {err}",
+                self.path.to_string_lossy()
+            )
+        })
+    }
+}
+
+fn rustc_mismatch_message(path: &Path, commit_hash: &str, toolchain: &str) -> String {
+    let describe = |commit_hash: &str, toolchain: &str| {
+        if toolchain.is_empty() {
+            format!("rustc commit `{commit_hash}`")
+        } else {
+            format!("rustc commit `{commit_hash}` (toolchain `{toolchain}`)")
+        }
+    };
+    let rebuild = if RUSTUP_TOOLCHAIN.is_empty() {
+        String::from("rebuild the library with the driver's toolchain")
+    } else {
+        format!("rebuild the library with toolchain `{RUSTUP_TOOLCHAIN}`")
+    };
+    format!(
+        "`{}` was built with {}, but the driver was built with {}; {rebuild}",
+        path.to_string_lossy(),
+        describe(commit_hash, toolchain),
+        describe(RUSTC_COMMIT_HASH, RUSTUP_TOOLCHAIN),
+    )
 }
 
 #[rustversion::before(2023-12-18)]
@@ -142,7 +240,15 @@ impl Callbacks {
                     early_error(msg);
                 });
 
-                loaded_libs.push(LoadedLibrary { path, lib });
+                let loaded_lib = LoadedLibrary { path, lib };
+
+                // smoelius: Check the library's rustc before anything in the library is called,
+                // i.e., before `register_lints` is called through a Rust-ABI function pointer.
+                if let Err(err) = loaded_lib.check_rustc() {
+                    early_error(err.to_string());
+                }
+
+                loaded_libs.push(loaded_lib);
             }
         }
         Self { loaded_libs }
@@ -245,6 +351,7 @@ impl rustc_driver::Callbacks for Callbacks {
             }
 
             let dylint_libs = env::var(env::DYLINT_LIBS).ok();
+            let dylint_lints = env::var(env::DYLINT_LINTS).ok();
             let dylint_metadata = env::var(env::DYLINT_METADATA).ok();
             let dylint_no_deps = env::var(env::DYLINT_NO_DEPS).ok();
             let dylint_no_deps_enabled = dylint_no_deps.as_ref().is_some_and(|value| value != "0");
@@ -254,6 +361,10 @@ impl rustc_driver::Callbacks for Callbacks {
                 rustc_span::Symbol::intern(env::DYLINT_LIBS),
                 dylint_libs.as_deref().map(rustc_span::Symbol::intern),
             ));
+            sess.parse_sess().env_depinfo.lock().insert((
+                rustc_span::Symbol::intern(env::DYLINT_LINTS),
+                dylint_lints.as_deref().map(rustc_span::Symbol::intern),
+            ));
             sess.parse_sess().env_depinfo.lock().insert((
                 rustc_span::Symbol::intern(env::DYLINT_METADATA),
                 dylint_metadata.as_deref().map(rustc_span::Symbol::intern),
@@ -267,6 +378,11 @@ impl rustc_driver::Callbacks for Callbacks {
                 return;
             }
 
+            if config_schemas_enabled() {
+                list_config_schemas(&loaded_libs);
+                std::process::exit(0);
+            }
+
             let mut before = BTreeSet::<Lint>::new();
             if list_enabled() {
                 lint_store.get_lints().iter().for_each(|&lint| {
@@ -282,14 +398,16 @@ impl rustc_driver::Callbacks for Callbacks {
                 }
                 loaded_lib.register_lints(sess, lint_store);
             }
+            register_lint_groups(sess, lint_store, &loaded_libs);
             if list_enabled() {
                 let mut after = BTreeSet::<Lint>::new();
                 lint_store.get_lints().iter().for_each(|&lint| {
                     after.insert(lint.into());
                 });
-                list_lints(&before, &after);
+                list_lints(lint_store, &before, &after);
                 std::process::exit(0);
             }
+            check_lints(sess, lint_store, &loaded_libs, &lints());
         }));
 
         register_extra_symbols(config);
@@ -300,38 +418,184 @@ impl rustc_driver::Callbacks for Callbacks {
     }
 }
 
+// smoelius: rustc's `LintStore::register_group` ICEs if a group is registered twice. So rather than
+// have each library register its own groups, the groups of all loaded libraries are merged and
+// registered here, once.
+fn register_lint_groups(
+    sess: &rustc_session::Session,
+    lint_store: &mut rustc_lint::LintStore,
+    loaded_libs: &[LoadedLibrary],
+) {
+    let mut groups = BTreeMap::<String, BTreeSet<String>>::new();
+    for loaded_lib in loaded_libs {
+        match loaded_lib.lint_groups() {
+            Ok(lib_groups) => {
+                for (group, names) in lib_groups {
+                    groups.entry(group).or_default().extend(names);
+                }
+            }
+            Err(err) => {
+                session_err(sess, &err);
+            }
+        }
+    }
+
+    let mut existing = BTreeSet::new();
+    for (name, _, _) in lint_store.get_lint_groups() {
+        existing.insert(name);
+    }
+
+    for (group, names) in groups {
+        if existing.contains(group.as_str()) {
+            session_err(
+                sess,
+                &format!("lint group `{group}` conflicts with an existing lint group"),
+            );
+            continue;
+        }
+        let lint_ids = lint_store
+            .get_lints()
+            .iter()
+            .map(|&lint| rustc_lint::LintId::of(lint))
+            .filter(|lint_id| names.contains(&lint_id.to_string()))
+            .collect();
+        // smoelius: `register_group` requires a `&'static str`. Each group is registered once, so
+        // leaking its name is fine.
+        lint_store.register_group(true, Box::leak(group.into_boxed_str()), None, lint_ids);
+    }
+}
+
 #[must_use]
 fn list_enabled() -> bool {
     env::var(env::DYLINT_LIST).is_ok_and(|value| value != "0")
 }
 
-fn list_lints(before: &BTreeSet<Lint>, after: &BTreeSet<Lint>) {
-    let difference: Vec<Lint> = after.difference(before).cloned().collect();
+#[must_use]
+fn config_schemas_enabled() -> bool {
+    env::var(env::DYLINT_CONFIG_SCHEMAS).is_ok_and(|value| value != "0")
+}
 
-    let name_width = difference
-        .iter()
-        .map(|lint| lint.name.len())
-        .max()
-        .unwrap_or_default();
+fn list_config_schemas(loaded_libs: &[LoadedLibrary]) {
+    let mut schemas = serde_json::Map::new();
+    for loaded_lib in loaded_libs {
+        let lib_schemas = loaded_lib.config_schemas().unwrap_or_else(|err| {
+            let msg = err.to_string();
+            early_error(msg);
+        });
+        schemas.extend(lib_schemas);
+    }
+
+    let json = serde_json::Value::Object(schemas).to_string();
+
+    println!("{json}");
+}
+
+fn list_lints(lint_store: &rustc_lint::LintStore, before: &BTreeSet<Lint>, after: &BTreeSet<Lint>) {
+    let pass_kinds = pass_kinds(lint_store);
+
+    let mut groups = BTreeMap::<String, Vec<String>>::new();
+    for (group, lint_ids, _) in lint_store.get_lint_groups() {
+        for lint_id in lint_ids {
+            groups
+                .entry(lint_id.to_string())
+                .or_default()
+                .push(group.to_owned());
+        }
+    }
+
+    let lints = after
+        .difference(before)
+        .map(|lint| list::Lint {
+            name: lint.name.to_lowercase(),
+            level: lint.level.as_str().to_owned(),
+            description: lint.desc.to_owned(),
+            pass_kind: pass_kinds
+                .as_ref()
+                .and_then(|pass_kinds| pass_kinds.get(lint.name).copied()),
+            groups: groups
+                .get(&lint.name.to_lowercase())
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    let json = serde_json::to_string(&lints).unwrap_or_else(|error| {
+        let msg = format!("could not serialize lints: {error}");
+        early_error(msg);
+    });
+
+    println!("{json}");
+}
 
-    let level_width = difference
+// smoelius: The levels in `DYLINT_LINTS` are passed to rustc as command-line flags (see
+// `rustc_args`). rustc merely warns about unknown lints on the command line, and the warning does
+// not say where the lint came from. So unknown lints are reported here, after the libraries have
+// registered their lints.
+fn check_lints(
+    sess: &rustc_session::Session,
+    lint_store: &rustc_lint::LintStore,
+    loaded_libs: &[LoadedLibrary],
+    lints: &[(String, String)],
+) {
+    let mut known = lint_store
+        .get_lints()
         .iter()
-        .map(|lint| lint.level.as_str().len())
-        .max()
-        .unwrap_or_default();
+        .map(|lint| lint.name_lower())
+        .collect::<BTreeSet<_>>();
+    for (name, _, _) in lint_store.get_lint_groups() {
+        known.insert(name.to_owned());
+    }
+    known.insert(String::from("warnings"));
 
-    for Lint { name, level, desc } in difference {
-        println!(
-            "    {:<name_width$}    {:<level_width$}    {}",
-            name.to_lowercase(),
-            level.as_str(),
-            desc,
-            name_width = name_width,
-            level_width = level_width
+    for (name, _) in lints {
+        if known.contains(&name.replace('-', "_")) {
+            continue;
+        }
+        let names = loaded_libs
+            .iter()
+            .filter_map(|loaded_lib| parse_path_filename(&loaded_lib.path))
+            .map(|(lib_name, _)| format!("`{lib_name}`"))
+            .collect::<Vec<_>>();
+        let libraries = if names.is_empty() {
+            String::from("no libraries were loaded")
+        } else {
+            format!("loaded libraries: {}", names.join(", "))
+        };
+        session_err(
+            sess,
+            &format!("`dylint.toml` sets the level of unknown lint `{name}` ({libraries})"),
         );
     }
 }
 
+#[rustversion::before(2024-11-01)]
+fn pass_kinds(_lint_store: &rustc_lint::LintStore) -> Option<BTreeMap<&'static str, PassKind>> {
+    None
+}
+
+// smoelius: Late lint passes cannot be constructed without a `TyCtxt`. So the pre-expansion and
+// early lint passes are constructed and asked for their lints, and any remaining lints are assumed
+// to belong to late lint passes. Note that `get_lints` is a method of the `LintPass` trait only in
+// relatively recent toolchains.
+#[rustversion::since(2024-11-01)]
+fn pass_kinds(lint_store: &rustc_lint::LintStore) -> Option<BTreeMap<&'static str, PassKind>> {
+    let mut pass_kinds = BTreeMap::new();
+    for (passes, pass_kind) in [
+        (&lint_store.pre_expansion_passes, PassKind::PreExpansion),
+        (&lint_store.early_passes, PassKind::Early),
+    ] {
+        for pass in passes {
+            for lint in pass().get_lints() {
+                pass_kinds.entry(lint.name).or_insert(pass_kind);
+            }
+        }
+    }
+    for lint in lint_store.get_lints() {
+        pass_kinds.entry(lint.name).or_insert(PassKind::Late);
+    }
+    Some(pass_kinds)
+}
+
 #[rustversion::before(2025-05-14)]
 fn register_extra_symbols(_config: &mut rustc_interface::Config) {}
 
@@ -345,7 +609,12 @@ fn register_extra_symbols(config: &mut rustc_interface::Config) {
 
 pub fn dylint_driver<T: AsRef<OsStr>>(args: &[T]) -> Result<()> {
     if args.len() <= 1 || args.iter().any(|arg| arg.as_ref() == "-V") {
-        println!("{} {}", env!("RUSTUP_TOOLCHAIN"), env!("CARGO_PKG_VERSION"));
+        println!(
+            "{} {}+protocol.{}",
+            env!("RUSTUP_TOOLCHAIN"),
+            env!("CARGO_PKG_VERSION"),
+            dylint_internal::DRIVER_PROTOCOL_VERSION
+        );
         return Ok(());
     }
 
@@ -356,8 +625,9 @@ pub fn run<T: AsRef<OsStr>>(args: &[T]) -> Result<()> {
     let sysroot = sysroot().ok();
     let rustflags = rustflags();
     let paths = paths();
+    let lints = lints();
 
-    let rustc_args = rustc_args(args, sysroot.as_deref(), &rustflags, &paths)?;
+    let rustc_args = rustc_args(args, sysroot.as_deref(), &rustflags, &paths, &lints)?;
 
     let mut callbacks = Callbacks::new(paths);
 
@@ -391,11 +661,20 @@ fn paths() -> Vec<PathBuf> {
     .unwrap_or_default()
 }
 
+fn lints() -> Vec<(String, String)> {
+    (|| -> Result<_> {
+        let dylint_lints = env::var(env::DYLINT_LINTS)?;
+        serde_json::from_str(&dylint_lints).map_err(Into::into)
+    })()
+    .unwrap_or_default()
+}
+
 fn rustc_args<T: AsRef<OsStr>, U: AsRef<str>, V: AsRef<Path>>(
     args: &[T],
     sysroot: Option<&Path>,
     rustflags: &[U],
     paths: &[V],
+    lints: &[(String, String)],
 ) -> Result<Vec<String>> {
     let mut args = args.iter().peekable();
     let mut rustc_args = Vec::new();
@@ -425,6 +704,11 @@ fn rustc_args<T: AsRef<OsStr>, U: AsRef<str>, V: AsRef<Path>>(
             bail!("could not parse `{}`", path.as_ref().to_string_lossy());
         }
     }
+    // smoelius: The levels come before the remaining arguments so that, as with Cargo's `[lints]`
+    // table, flags in `RUSTFLAGS` and `DYLINT_RUSTFLAGS` take precedence.
+    for (name, level) in lints {
+        rustc_args.push(format!("--{level}={name}"));
+    }
     rustc_args.extend(args.map(|s| s.as_ref().to_string_lossy().to_string()));
     rustc_args.extend(
         rustflags
@@ -475,6 +759,14 @@ mod test {
         assert!(matches!(version_meta().unwrap().channel, Channel::Nightly));
     }
 
+    #[test]
+    fn rustc_commit_hash() {
+        assert_eq!(
+            version_meta().unwrap().commit_hash.as_deref(),
+            Some(RUSTC_COMMIT_HASH)
+        );
+    }
+
     #[test]
     fn no_rustc() {
         assert_eq!(
@@ -482,13 +774,40 @@ mod test {
                 &["--crate-name", "name"],
                 None,
                 &[] as &[&str],
-                &[] as &[&Path]
+                &[] as &[&Path],
+                &[]
             )
             .unwrap(),
             vec!["rustc", "--crate-name", "name"]
         );
     }
 
+    #[test]
+    fn lint_levels() {
+        assert_eq!(
+            rustc_args(
+                &["rustc", "--crate-name", "name"],
+                None,
+                &["-D", "warnings"],
+                &[] as &[&Path],
+                &[
+                    (String::from("unused"), String::from("allow")),
+                    (String::from("unused_variables"), String::from("force-warn"))
+                ]
+            )
+            .unwrap(),
+            vec![
+                "rustc",
+                "--allow=unused",
+                "--force-warn=unused_variables",
+                "--crate-name",
+                "name",
+                "-D",
+                "warnings"
+            ]
+        );
+    }
+
     #[test]
     fn plain_rustc() {
         assert_eq!(
@@ -496,7 +815,8 @@ mod test {
                 &["rustc", "--crate-name", "name"],
                 None,
                 &[] as &[&str],
-                &[] as &[&Path]
+                &[] as &[&Path],
+                &[]
             )
             .unwrap(),
             vec!["rustc", "--crate-name", "name"]
@@ -510,7 +830,8 @@ mod test {
                 &["/bin/rustc", "--crate-name", "name"],
                 None,
                 &[] as &[&str],
-                &[] as &[&Path]
+                &[] as &[&Path],
+                &[]
             )
             .unwrap(),
             vec!["/bin/rustc", "--crate-name", "name"]
@@ -0,0 +1,166 @@
+use crate::{NameToolchainMap, driver_builder::format_size, opts, target_dirs};
+use anyhow::{Context, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{read_dir, remove_dir_all, symlink_metadata},
+    path::{Path, PathBuf},
+};
+
+/// A directory of build artifacts for one toolchain
+#[derive(Debug)]
+struct Tree {
+    toolchain: String,
+    /// The names of the discovered libraries built in the same workspace as this tree's. Empty for
+    /// the trees used for checking.
+    libraries: BTreeSet<String>,
+}
+
+pub fn clean(opts: &opts::Dylint, clean_opts: &opts::Clean) -> Result<()> {
+    let name_toolchain_map = NameToolchainMap::new(opts);
+    let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
+
+    let referenced_toolchains = name_toolchain_map
+        .values()
+        .flat_map(|toolchain_map| toolchain_map.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let trees = trees(opts, name_toolchain_map)?;
+
+    let lib_sel = opts.library_selection();
+
+    let mut total = 0;
+
+    for (path, tree) in &trees {
+        if !lib_sel.libs.is_empty() && !lib_sel.libs.iter().any(|lib| tree.libraries.contains(lib))
+        {
+            continue;
+        }
+        if clean_opts
+            .toolchain
+            .as_ref()
+            .is_some_and(|toolchain| *toolchain != tree.toolchain)
+        {
+            continue;
+        }
+        if clean_opts.unreferenced && referenced_toolchains.contains(&tree.toolchain) {
+            continue;
+        }
+
+        let size = dir_size(path)?;
+        total += size;
+
+        if clean_opts.dry_run {
+            println!("Would remove `{}` ({})", path.display(), format_size(size));
+        } else {
+            if !opts.quiet {
+                eprintln!("Removing `{}` ({})", path.display(), format_size(size));
+            }
+            remove_dir_all(path)
+                .with_context(|| format!("`remove_dir_all` failed for `{}`", path.display()))?;
+        }
+    }
+
+    if clean_opts.dry_run {
+        println!("Would free {}", format_size(total));
+    } else if !opts.quiet {
+        eprintln!("Freed {}", format_size(total));
+    }
+
+    Ok(())
+}
+
+// smoelius: Libraries are built in `target/dylint/libraries/<toolchain>` within their own
+// workspaces. Trees for toolchains that no discovered library uses (e.g., old nightlies) are found
+// by listing the parents of the discovered libraries' target directories.
+fn trees(
+    opts: &opts::Dylint,
+    name_toolchain_map: &crate::name_toolchain_map::NameToolchainMap,
+) -> Result<BTreeMap<PathBuf, Tree>> {
+    let mut library_roots = BTreeMap::<PathBuf, BTreeSet<String>>::new();
+
+    for (name, toolchain_map) in name_toolchain_map {
+        for maybe_library in toolchain_map.values().flatten() {
+            if let Some(parent) = maybe_library
+                .target_directory()
+                .as_deref()
+                .and_then(Path::parent)
+            {
+                library_roots
+                    .entry(parent.to_path_buf())
+                    .or_default()
+                    .insert(name.clone());
+            }
+        }
+    }
+
+    let mut trees = BTreeMap::new();
+
+    for (root, libraries) in library_roots {
+        for (toolchain, path) in subdirectories(&root)? {
+            trees.insert(
+                path,
+                Tree {
+                    toolchain,
+                    libraries: libraries.clone(),
+                },
+            );
+        }
+    }
+
+    // smoelius: The trees used for checking are found only when there is a workspace to check.
+    if let Ok(target_dirs) = target_dirs(opts) {
+        for (toolchain, path) in subdirectories(&target_dirs)? {
+            trees.insert(
+                path,
+                Tree {
+                    toolchain,
+                    libraries: BTreeSet::new(),
+                },
+            );
+        }
+    }
+
+    Ok(trees)
+}
+
+fn subdirectories(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut subdirectories = Vec::new();
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        if path.is_dir() {
+            subdirectories.push((entry.file_name().to_string_lossy().to_string(), path));
+        }
+    }
+
+    Ok(subdirectories)
+}
+
+fn dir_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        // smoelius: Use `symlink_metadata` so that symlinks are not followed.
+        let metadata = symlink_metadata(&path)
+            .with_context(|| format!("`symlink_metadata` failed for `{}`", path.display()))?;
+        if metadata.is_dir() {
+            size += dir_size(&path)?;
+        } else {
+            size += metadata.len();
+        }
+    }
+
+    Ok(size)
+}
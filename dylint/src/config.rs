@@ -0,0 +1,331 @@
+use crate::{ToolchainMap, driver_builder, opts, print_json};
+use anyhow::{Context, Result, anyhow, ensure};
+use cargo_metadata::MetadataCommand;
+use dylint_internal::{CommandExt, driver as dylint_driver, env, parse_path_filename};
+use serde_json::{Map, Value, json};
+use std::{path::Path, process::Stdio};
+
+// smoelius: The levels that the `lints` table accepts. See `LintLevel` in `library_packages`.
+const LINT_LEVELS: [&str; 5] = ["allow", "warn", "deny", "forbid", "force-warn"];
+
+pub fn print_schema(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
+    let schema = combined_schema(opts, resolved)?;
+    print_json(&schema)
+}
+
+pub fn validate(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
+    let schema = combined_schema(opts, resolved)?;
+
+    let mut command = MetadataCommand::new();
+    if let Some(path) = &opts.library_selection().manifest_path {
+        command.manifest_path(path);
+    }
+    let metadata = command.no_deps().exec()?;
+
+    let sources = dylint_internal::config::sources(&metadata)
+        .map_err(|error| anyhow!("Could not read Dylint configuration: {error}"))?;
+
+    let mut errors = Vec::new();
+
+    for (source, table) in &sources {
+        let value = serde_json::to_value(table)?;
+        let mut source_errors = Vec::new();
+        check(&schema, &value, "", &mut source_errors);
+        errors.extend(
+            source_errors
+                .into_iter()
+                .map(|error| format!("{source}: {error}")),
+        );
+    }
+
+    ensure!(
+        errors.is_empty(),
+        "Found {} problem(s) in Dylint configuration:\n    {}",
+        errors.len(),
+        errors.join("\n    ")
+    );
+
+    Ok(())
+}
+
+fn combined_schema(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<Value> {
+    let mut properties = Map::new();
+
+    for (toolchain, paths) in resolved {
+        for path in paths {
+            let (name, _) =
+                parse_path_filename(path).ok_or_else(|| anyhow!("Could not parse path"))?;
+
+            let schemas = library_config_schemas(opts, toolchain, path)?;
+
+            // smoelius: A library that does not export schemas may still read configuration. So
+            // allow anything under its name.
+            if schemas.is_empty() {
+                properties.entry(name).or_insert_with(|| json!({}));
+            }
+
+            properties.extend(schemas);
+        }
+    }
+
+    properties.insert("lints".to_owned(), lints_schema());
+    properties.insert("workspace".to_owned(), json!({ "type": "object" }));
+
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    }))
+}
+
+fn lints_schema() -> Value {
+    json!({
+        "type": "object",
+        "additionalProperties": {
+            "anyOf": [
+                { "enum": LINT_LEVELS },
+                {
+                    "type": "object",
+                    "properties": {
+                        "level": { "enum": LINT_LEVELS },
+                        "priority": { "type": "integer" },
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        },
+    })
+}
+
+fn library_config_schemas(
+    opts: &opts::Dylint,
+    toolchain: &str,
+    path: &Path,
+) -> Result<Map<String, Value>> {
+    let driver = driver_builder::get(opts, toolchain)?;
+    let dylint_libs = serde_json::to_string(&[path])?;
+
+    // smoelius: As in `library_lints`, `-W help` ensures the library gets loaded.
+    let mut command = dylint_driver(toolchain, &driver)?;
+    let output = command
+        .envs([
+            (env::DYLINT_CONFIG_SCHEMAS, "1"),
+            (env::DYLINT_LIBS, dylint_libs.as_str()),
+        ])
+        .args(["rustc", "-W", "help"])
+        .stderr(Stdio::inherit())
+        .logged_output(true)?;
+
+    serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "Could not parse configuration schemas for `{}`",
+            path.display()
+        )
+    })
+}
+
+// smoelius: `check` implements just the parts of JSON Schema that `config_schema` and
+// `combined_schema` generate.
+fn check(schema: &Value, value: &Value, pointer: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    let at = if pointer.is_empty() {
+        String::new()
+    } else {
+        format!("`{pointer}`: ")
+    };
+
+    if let Some(Value::String(ty)) = schema.get("type")
+        && !has_type(value, ty)
+    {
+        errors.push(format!("{at}expected {ty}, found {}", describe(value)));
+        return;
+    }
+
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+        && value.as_f64().is_some_and(|n| n < minimum)
+    {
+        errors.push(format!("{at}{value} is less than {minimum}"));
+    }
+
+    if let Some(Value::Array(variants)) = schema.get("enum")
+        && !variants.contains(value)
+    {
+        errors.push(format!(
+            "{at}expected one of {}, found {value}",
+            list(variants)
+        ));
+    }
+
+    if let Some(Value::Array(alternatives)) = schema.get("anyOf") {
+        let mut alternative_errors = Vec::new();
+        let matched = alternatives.iter().any(|alternative| {
+            let mut errors = Vec::new();
+            check(alternative, value, pointer, &mut errors);
+            let matched = errors.is_empty();
+            alternative_errors.extend(errors);
+            matched
+        });
+        if !matched {
+            errors.extend(alternative_errors);
+        }
+    }
+
+    if let Some(items) = schema.get("items")
+        && let Value::Array(elements) = value
+    {
+        for (i, element) in elements.iter().enumerate() {
+            check(items, element, &format!("{pointer}/{i}"), errors);
+        }
+    }
+
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    if let Some(min) = schema.get("minProperties").and_then(as_usize)
+        && object.len() < min
+    {
+        errors.push(format!("{at}expected at least {min} key(s)"));
+    }
+
+    if let Some(max) = schema.get("maxProperties").and_then(as_usize)
+        && object.len() > max
+    {
+        errors.push(format!("{at}expected at most {max} key(s)"));
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for (key, value) in object {
+        let key_pointer = format!("{pointer}/{key}");
+
+        if let Some(property_names) = schema.get("propertyNames") {
+            check(property_names, &Value::String(key.clone()), pointer, errors);
+        }
+
+        if let Some(property) = properties.and_then(|properties| properties.get(key)) {
+            check(property, value, &key_pointer, errors);
+            continue;
+        }
+
+        match schema.get("additionalProperties") {
+            Some(Value::Bool(false)) if pointer.is_empty() => {
+                errors.push(format!(
+                    "unknown key `{key}`; it is not the name of a loaded library"
+                ));
+            }
+            Some(Value::Bool(false)) => {
+                errors.push(format!("{at}unknown key `{key}`"));
+            }
+            Some(additional) => {
+                check(additional, value, &key_pointer, errors);
+            }
+            None => {}
+        }
+    }
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "array" => value.is_array(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "object" => value.is_object(),
+        "string" => value.is_string(),
+        _ => true,
+    }
+}
+
+fn as_usize(value: &Value) -> Option<usize> {
+    value.as_u64().and_then(|n| usize::try_from(n).ok())
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn list(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn errors(schema: &Value, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        check(schema, value, "", &mut errors);
+        errors
+    }
+
+    #[test]
+    fn unknown_library() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "a": {} },
+            "additionalProperties": false,
+        });
+        assert!(errors(&schema, &json!({ "a": 1 })).is_empty());
+        assert_eq!(
+            errors(&schema, &json!({ "b": 1 })),
+            ["unknown key `b`; it is not the name of a loaded library"]
+        );
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "a": {
+                    "type": "object",
+                    "properties": { "threshold": { "type": "integer", "minimum": 0 } },
+                    "additionalProperties": false,
+                },
+            },
+        });
+        assert_eq!(
+            errors(&schema, &json!({ "a": { "threshold": "ten" } })),
+            ["`/a/threshold`: expected integer, found string"]
+        );
+        assert_eq!(
+            errors(&schema, &json!({ "a": { "threshold": -1 } })),
+            ["`/a/threshold`: -1 is less than 0"]
+        );
+        assert_eq!(
+            errors(&schema, &json!({ "a": { "threshhold": 10 } })),
+            ["`/a`: unknown key `threshhold`"]
+        );
+    }
+
+    #[test]
+    fn lint_levels() {
+        let schema = json!({ "properties": { "lints": lints_schema() } });
+        assert!(
+            errors(
+                &schema,
+                &json!({ "lints": { "a": "deny", "b": { "level": "warn", "priority": 1 } } })
+            )
+            .is_empty()
+        );
+        assert!(!errors(&schema, &json!({ "lints": { "a": "loud" } })).is_empty());
+    }
+}
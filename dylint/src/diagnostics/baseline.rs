@@ -0,0 +1,375 @@
+//! Baselines, i.e., recorded findings that should not be reported again
+//!
+//! A finding is identified by a [`Fingerprint`] consisting of the lint's name, the file in which
+//! the finding occurs, the path of the item enclosing the finding, and a hash of the finding's
+//! normalized source snippet. Line numbers are deliberately excluded so that a baseline survives
+//! unrelated edits.
+
+use anyhow::{Context, Result, ensure};
+use cargo_metadata::diagnostic::Diagnostic;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+const VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Baseline {
+    version: u32,
+    findings: Vec<Fingerprint>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Fingerprint {
+    lint: String,
+    file: String,
+    item_path: String,
+    snippet_hash: String,
+}
+
+impl Baseline {
+    #[must_use]
+    pub fn new(mut findings: Vec<Fingerprint>) -> Self {
+        findings.sort();
+        Self {
+            version: VERSION,
+            findings,
+        }
+    }
+
+    pub fn read(path: &str) -> Result<Self> {
+        let contents = read_to_string(path).with_context(|| format!("Could not read `{path}`"))?;
+        let baseline = serde_json::from_str::<Self>(&contents)
+            .with_context(|| format!("Could not parse `{path}`"))?;
+        ensure!(
+            baseline.version == VERSION,
+            "`{path}` has unsupported version {}",
+            baseline.version
+        );
+        Ok(baseline)
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` in `{}`", self.lint, self.file)?;
+        if !self.item_path.is_empty() {
+            write!(f, " (`{}`)", self.item_path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes fingerprints for diagnostics produced by the loaded libraries' lints
+pub struct Fingerprinter {
+    workspace_root: PathBuf,
+    lints: BTreeSet<String>,
+    sources: BTreeMap<String, Option<String>>,
+}
+
+impl Fingerprinter {
+    #[must_use]
+    pub fn new(workspace_root: &Path, lints: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            workspace_root: workspace_root.to_path_buf(),
+            lints: lints.into_iter().collect(),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Returns `None` if `diagnostic` was not produced by a loaded lint, or if it has no primary
+    /// span.
+    pub fn fingerprint(&mut self, diagnostic: &Diagnostic) -> Option<Fingerprint> {
+        let lint = &diagnostic.code.as_ref()?.code;
+        if !self.lints.contains(lint) {
+            return None;
+        }
+
+        let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+
+        // smoelius: Highlight columns are 1-based and count characters, not bytes.
+        let snippet = span
+            .text
+            .iter()
+            .map(|line| {
+                line.text
+                    .chars()
+                    .skip(line.highlight_start.saturating_sub(1))
+                    .take(line.highlight_end.saturating_sub(line.highlight_start))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let item_path = self
+            .source(&span.file_name)
+            .map(|source| item_path(source, span.byte_start as usize))
+            .unwrap_or_default();
+
+        Some(Fingerprint {
+            lint: lint.clone(),
+            file: span.file_name.replace('\\', "/"),
+            item_path,
+            snippet_hash: format!("{:016x}", fnv1a(snippet.as_bytes())),
+        })
+    }
+
+    fn source(&mut self, file_name: &str) -> Option<&str> {
+        self.sources
+            .entry(file_name.to_owned())
+            .or_insert_with(|| read_to_string(self.workspace_root.join(file_name)).ok())
+            .as_deref()
+    }
+}
+
+/// Tracks which of a baseline's findings have been seen
+pub struct Matcher {
+    remaining: BTreeMap<Fingerprint, usize>,
+}
+
+impl Matcher {
+    #[must_use]
+    pub fn new(baseline: Baseline) -> Self {
+        let mut remaining = BTreeMap::new();
+        for fingerprint in baseline.findings {
+            *remaining.entry(fingerprint).or_default() += 1;
+        }
+        Self { remaining }
+    }
+
+    /// Returns true if `fingerprint` is in the baseline and has not already been matched as many
+    /// times as it occurs there.
+    pub fn matches(&mut self, fingerprint: &Fingerprint) -> bool {
+        let Some(count) = self.remaining.get_mut(fingerprint) else {
+            return false;
+        };
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+
+    /// Returns the baseline's findings that were not matched
+    #[must_use]
+    pub fn unmatched(&self) -> Vec<&Fingerprint> {
+        self.remaining
+            .iter()
+            .flat_map(|(fingerprint, &count)| std::iter::repeat_n(fingerprint, count))
+            .collect()
+    }
+}
+
+const ITEM_KEYWORDS: [&str; 7] = ["enum", "fn", "impl", "mod", "struct", "trait", "union"];
+
+// smoelius: Rather than pull in a Rust parser, approximate the enclosing item path by tracking
+// braces. Each `{` is attributed to the item (if any) whose header precedes it. Comments, strings,
+// and character literals are skipped so that braces within them are not counted.
+fn item_path(source: &str, offset: usize) -> String {
+    let bytes = source.as_bytes();
+    let end = offset.min(bytes.len());
+    let mut stack = Vec::new();
+    let mut header_start = 0;
+    let mut i = 0;
+    while i < end {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = find(bytes, i, b"\n");
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find(bytes, i + 2, b"*/") + 1;
+            }
+            b'r' => {
+                if let Some(hashes) = raw_string_hashes(bytes, i) {
+                    let mut terminator = vec![b'"'];
+                    terminator.resize(hashes + 1, b'#');
+                    i = find(bytes, i + hashes + 2, &terminator) + hashes;
+                }
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'\'' if bytes.get(i + 1) == Some(&b'\\') => {
+                i = find(bytes, i + 3, b"'");
+            }
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => {
+                i += 2;
+            }
+            b'{' => {
+                stack.push(item_name(source.get(header_start..i).unwrap_or_default()));
+                header_start = i + 1;
+            }
+            b'}' => {
+                stack.pop();
+                header_start = i + 1;
+            }
+            b';' => {
+                header_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    stack.into_iter().flatten().collect::<Vec<_>>().join("::")
+}
+
+/// If a raw string literal (e.g., `r#"..."#` or `br"..."`) starts with the `r` at `i`, returns the
+/// number of `#`s that delimit it.
+fn raw_string_hashes(bytes: &[u8], i: usize) -> Option<usize> {
+    let is_ident = |j: usize| bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_';
+    let prefix_start = match i.checked_sub(1) {
+        Some(j) if matches!(bytes[j], b'b' | b'c') => j,
+        _ => i,
+    };
+    if prefix_start.checked_sub(1).is_some_and(is_ident) {
+        return None;
+    }
+    let hashes = bytes
+        .get(i + 1..)?
+        .iter()
+        .take_while(|&&byte| byte == b'#')
+        .count();
+    (bytes.get(i + 1 + hashes) == Some(&b'"')).then_some(hashes)
+}
+
+/// Returns the index of the first occurrence of `needle` in `bytes` at or after `start`, or
+/// `bytes.len()` if there is none.
+fn find(bytes: &[u8], start: usize, needle: &[u8]) -> usize {
+    bytes
+        .get(start..)
+        .and_then(|rest| {
+            rest.windows(needle.len())
+                .position(|window| window == needle)
+        })
+        .map_or(bytes.len(), |position| start + position)
+}
+
+fn item_name(header: &str) -> Option<String> {
+    let header = header
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let header = strip_generics(&header);
+    let words = header
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let index = words.iter().position(|word| ITEM_KEYWORDS.contains(word))?;
+    let rest = &words[index + 1..];
+    if words[index] == "impl" {
+        let rest = rest
+            .iter()
+            .position(|&word| word == "where")
+            .map_or(rest, |where_index| &rest[..where_index]);
+        let self_ty = rest
+            .iter()
+            .position(|&word| word == "for")
+            .map_or(rest, |for_index| &rest[for_index + 1..]);
+        return self_ty.last().map(|word| format!("<impl {word}>"));
+    }
+    rest.first().map(|&word| word.to_owned())
+}
+
+fn strip_generics(header: &str) -> String {
+    let mut depth = 0usize;
+    header
+        .chars()
+        .filter(|&c| match c {
+            '<' => {
+                depth += 1;
+                false
+            }
+            '>' if depth > 0 => {
+                depth -= 1;
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect()
+}
+
+// smoelius: FNV-1a is used because, unlike `DefaultHasher`, its output is guaranteed not to change
+// between Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn item_path_nested() {
+        let source = r#"
+mod m {
+    struct S;
+
+    impl<T> Trait<T> for S where T: Clone {
+        fn f(&self) {
+            let s = "{";
+            let c = '}';
+            // {
+            if true {
+                HERE
+            }
+        }
+    }
+}
+"#;
+        let offset = source.find("HERE").unwrap();
+        assert_eq!("m::<impl S>::f", item_path(source, offset));
+    }
+
+    #[test]
+    fn item_path_raw_string() {
+        let source = r##"
+mod m {
+    fn f() {
+        let s = r#"}"{"#;
+        let t = br"}";
+        HERE
+    }
+}
+"##;
+        let offset = source.find("HERE").unwrap();
+        assert_eq!("m::f", item_path(source, offset));
+    }
+
+    #[test]
+    fn item_path_top_level() {
+        let source = "fn f() {}\n\nHERE";
+        let offset = source.find("HERE").unwrap();
+        assert_eq!("", item_path(source, offset));
+    }
+
+    #[test]
+    fn matcher_counts() {
+        let fingerprint = Fingerprint {
+            lint: String::from("lint"),
+            file: String::from("src/lib.rs"),
+            item_path: String::from("f"),
+            snippet_hash: format!("{:016x}", fnv1a(b"x")),
+        };
+        let mut matcher = Matcher::new(Baseline::new(vec![fingerprint.clone()]));
+        assert!(matcher.matches(&fingerprint));
+        assert!(!matcher.matches(&fingerprint));
+        assert!(matcher.unmatched().is_empty());
+    }
+}
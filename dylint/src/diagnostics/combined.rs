@@ -0,0 +1,133 @@
+//! A single human-readable report covering all toolchains
+//!
+//! Each toolchain's `cargo check` compiles the same crates, so diagnostics not produced by Dylint
+//! lints (e.g., rustc's built-in warnings) would otherwise appear once per toolchain. Such
+//! diagnostics are reported only once. Diagnostics produced by Dylint lints are tagged with the
+//! name of the library that produced them.
+
+use super::{Library, ToolchainDiagnostic};
+use anyhow::Result;
+use cargo_metadata::diagnostic::{Diagnostic, DiagnosticLevel};
+use std::{collections::BTreeSet, io::Write};
+
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+struct Position<'a> {
+    file_name: &'a str,
+    line: usize,
+    column: usize,
+}
+
+pub fn render(
+    libraries: &[Library],
+    diagnostics: &[ToolchainDiagnostic],
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mut seen = BTreeSet::new();
+    let mut entries = Vec::new();
+
+    for ToolchainDiagnostic {
+        toolchain,
+        diagnostic,
+    } in diagnostics
+    {
+        // smoelius: Diagnostics without spans or codes are summaries like "N warnings emitted".
+        // They are replaced by the summary at the end of the report.
+        if diagnostic.spans.is_empty() && diagnostic.code.is_none() {
+            continue;
+        }
+
+        let library = library(libraries, toolchain, diagnostic);
+
+        if library.is_none() && !seen.insert(key(diagnostic)) {
+            continue;
+        }
+
+        entries.push((position(diagnostic), library, diagnostic));
+    }
+
+    // smoelius: `sort_by_key` is stable, so diagnostics at the same position stay in the order in
+    // which they were emitted. Diagnostics without a primary span are sorted last.
+    entries.sort_by_key(|(position, _, _)| (position.is_none(), *position));
+
+    let mut n_warnings = 0;
+    let mut n_errors = 0;
+
+    for (_, library, diagnostic) in entries {
+        match diagnostic.level {
+            DiagnosticLevel::Error | DiagnosticLevel::Ice => n_errors += 1,
+            DiagnosticLevel::Warning => n_warnings += 1,
+            _ => {}
+        }
+        let rendered = diagnostic
+            .rendered
+            .as_deref()
+            .unwrap_or(&diagnostic.message);
+        if let Some(library) = library {
+            write!(out, "[{library}] ")?;
+        }
+        write!(out, "{rendered}")?;
+        if !rendered.ends_with('\n') {
+            writeln!(out)?;
+        }
+    }
+
+    writeln!(
+        out,
+        "{n_warnings} warning(s) and {n_errors} error(s) across all toolchains"
+    )?;
+
+    Ok(())
+}
+
+/// Returns the name of the library whose lint produced `diagnostic`, if any
+fn library<'a>(
+    libraries: &'a [Library],
+    toolchain: &str,
+    diagnostic: &Diagnostic,
+) -> Option<&'a str> {
+    let code = &diagnostic.code.as_ref()?.code;
+    libraries
+        .iter()
+        .filter(|library| library.toolchain == toolchain)
+        .find(|library| library.lints.iter().any(|lint| lint.name == *code))
+        .map(|library| library.name.as_str())
+}
+
+type Key<'a> = (
+    String,
+    &'a str,
+    Option<&'a str>,
+    Vec<(&'a str, usize, usize, usize, usize)>,
+);
+
+// smoelius: The key deliberately excludes the rendered message, as different versions of rustc
+// may render the same diagnostic differently.
+fn key(diagnostic: &Diagnostic) -> Key<'_> {
+    (
+        format!("{:?}", diagnostic.level),
+        &diagnostic.message,
+        diagnostic.code.as_ref().map(|code| code.code.as_str()),
+        diagnostic
+            .spans
+            .iter()
+            .map(|span| {
+                (
+                    span.file_name.as_str(),
+                    span.line_start,
+                    span.column_start,
+                    span.line_end,
+                    span.column_end,
+                )
+            })
+            .collect(),
+    )
+}
+
+fn position(diagnostic: &Diagnostic) -> Option<Position<'_>> {
+    let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+    Some(Position {
+        file_name: &span.file_name,
+        line: span.line_start,
+        column: span.column_start,
+    })
+}
@@ -0,0 +1,45 @@
+//! Restriction of diagnostics to the lines that differ from a git ref
+
+use anyhow::{Context, Result};
+use cargo_metadata::diagnostic::Diagnostic;
+use std::{
+    collections::BTreeMap,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+pub struct ChangedLines {
+    workspace_root: PathBuf,
+    lines: BTreeMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl ChangedLines {
+    pub fn new(workspace_root: &Path, refname: &str) -> Result<Self> {
+        let workspace_root = workspace_root
+            .canonicalize()
+            .with_context(|| format!("Could not canonicalize `{}`", workspace_root.display()))?;
+        let lines = dylint_internal::changed_lines(&workspace_root, refname)?;
+        Ok(Self {
+            workspace_root,
+            lines,
+        })
+    }
+
+    /// Returns true if any of `diagnostic`'s primary spans overlaps a changed line.
+    #[must_use]
+    pub fn overlaps(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic
+            .spans
+            .iter()
+            .filter(|span| span.is_primary)
+            .any(|span| {
+                let Some(ranges) = self.lines.get(&self.workspace_root.join(&span.file_name))
+                else {
+                    return false;
+                };
+                ranges
+                    .iter()
+                    .any(|range| *range.start() <= span.line_end && span.line_start <= *range.end())
+            })
+    }
+}
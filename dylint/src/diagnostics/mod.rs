@@ -0,0 +1,203 @@
+use anyhow::{Result, ensure};
+use cargo_metadata::diagnostic::{Diagnostic, DiagnosticLevel};
+use dylint_internal::{CommandExt, list};
+use serde::Deserialize;
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+pub mod baseline;
+use baseline::{Fingerprint, Fingerprinter, Matcher};
+
+pub mod combined;
+
+pub mod diff;
+use diff::ChangedLines;
+
+pub mod sarif;
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    reason: String,
+    #[serde(rename = "message")]
+    diagnostic: Option<Diagnostic>,
+}
+
+/// A library that was loaded while checking, along with the lints it registered
+#[derive(Clone, Debug)]
+pub struct Library {
+    pub name: String,
+    pub toolchain: String,
+    pub lints: Vec<list::Lint>,
+}
+
+/// A diagnostic emitted while checking with a particular toolchain
+#[derive(Clone, Debug)]
+pub struct ToolchainDiagnostic {
+    pub toolchain: String,
+    pub diagnostic: Diagnostic,
+}
+
+/// Accumulates diagnostics across `cargo check` runs
+pub struct Collector {
+    pub libraries: Vec<Library>,
+    /// The diagnostics that were reported, i.e., not discarded
+    pub diagnostics: Vec<ToolchainDiagnostic>,
+    /// Fingerprints of the reported diagnostics that were produced by the loaded libraries' lints
+    pub findings: Vec<Fingerprint>,
+    pub fingerprinter: Option<Fingerprinter>,
+    pub matcher: Option<Matcher>,
+    /// If set, diagnostics outside of the changed lines are discarded
+    pub changed_lines: Option<ChangedLines>,
+}
+
+impl Collector {
+    #[must_use]
+    pub const fn new(libraries: Vec<Library>) -> Self {
+        Self {
+            libraries,
+            diagnostics: Vec::new(),
+            findings: Vec::new(),
+            fingerprinter: None,
+            matcher: None,
+            changed_lines: None,
+        }
+    }
+
+    /// Runs `command` and writes the rendered form of each diagnostic that is not discarded to
+    /// `rendered`. A diagnostic is discarded if it falls outside of the changed lines, or if it is
+    /// suppressed by the baseline.
+    ///
+    /// An error is returned if `command` fails, unless the only errors were ones produced by lints
+    /// and all of them were discarded.
+    ///
+    /// `command` should be a `cargo check` command that was passed [`message_format_arg`]. Lines of
+    /// standard output that are not JSON messages are passed through to standard output.
+    pub fn collect(
+        &mut self,
+        command: &mut Command,
+        toolchain: &str,
+        rendered: &mut dyn Write,
+    ) -> Result<()> {
+        let output = command.stdout(Stdio::piped()).logged_output(false)?;
+
+        self.process(command, toolchain, &output, rendered)
+    }
+
+    /// Like [`Collector::collect`], but for a `command` that has already been run
+    pub fn process(
+        &mut self,
+        command: &Command,
+        toolchain: &str,
+        output: &Output,
+        rendered: &mut dyn Write,
+    ) -> Result<()> {
+        let mut lint_errors = 0;
+        let mut other_errors = 0;
+        let mut reported_errors = 0;
+
+        let stdout = std::str::from_utf8(&output.stdout)?;
+        for line in stdout.lines() {
+            let Ok(message) = serde_json::from_str::<Message>(line) else {
+                println!("{line}");
+                continue;
+            };
+            if message.reason != "compiler-message" {
+                continue;
+            }
+            let Some(diagnostic) = message.diagnostic else {
+                continue;
+            };
+            let is_error = is_error(&diagnostic);
+            if is_error {
+                if is_lint(&diagnostic) {
+                    lint_errors += 1;
+                } else {
+                    other_errors += 1;
+                }
+            }
+            if !self.keep(&diagnostic) {
+                continue;
+            }
+            if is_error {
+                reported_errors += 1;
+            }
+            if let Some(s) = &diagnostic.rendered {
+                rendered.write_all(s.as_bytes())?;
+            }
+            self.diagnostics.push(ToolchainDiagnostic {
+                toolchain: toolchain.to_owned(),
+                diagnostic,
+            });
+        }
+
+        // smoelius: A `deny` lint causes `cargo check` to fail even if its diagnostics are
+        // discarded. So if the only errors were discarded lint errors, the failure is ignored.
+        let only_discarded_lint_errors =
+            lint_errors > 0 && other_errors == 0 && reported_errors == 0;
+
+        ensure!(
+            output.status.success() || only_discarded_lint_errors,
+            "command failed: {command:?}"
+        );
+
+        Ok(())
+    }
+
+    fn keep(&mut self, diagnostic: &Diagnostic) -> bool {
+        if let Some(changed_lines) = &self.changed_lines {
+            // smoelius: Diagnostics without spans are typically summaries like "N warnings
+            // emitted". Such summaries would be misleading once other diagnostics are discarded.
+            if diagnostic.spans.is_empty() || !changed_lines.overlaps(diagnostic) {
+                return false;
+            }
+        }
+        if let Some(fingerprint) = self
+            .fingerprinter
+            .as_mut()
+            .and_then(|fingerprinter| fingerprinter.fingerprint(diagnostic))
+        {
+            if self
+                .matcher
+                .as_mut()
+                .is_some_and(|matcher| matcher.matches(&fingerprint))
+            {
+                return false;
+            }
+            self.findings.push(fingerprint);
+        }
+        true
+    }
+}
+
+// smoelius: Errors without spans are things like "aborting due to N previous errors". They do not
+// indicate a problem on their own.
+const fn is_error(diagnostic: &Diagnostic) -> bool {
+    matches!(
+        diagnostic.level,
+        DiagnosticLevel::Error | DiagnosticLevel::Ice
+    ) && !diagnostic.spans.is_empty()
+}
+
+/// Returns true if `diagnostic` was produced by a lint, as opposed to, say, a type error
+fn is_lint(diagnostic: &Diagnostic) -> bool {
+    diagnostic.code.as_ref().is_some_and(|code| {
+        let is_error_code = code
+            .code
+            .strip_prefix('E')
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()));
+        !is_error_code
+    })
+}
+
+/// Returns the `--message-format` argument that `cargo check` must be passed for its diagnostics
+/// to be collected. If `ansi` is true, the rendered diagnostics will contain ANSI color codes.
+#[must_use]
+pub const fn message_format_arg(ansi: bool) -> &'static str {
+    if ansi {
+        "--message-format=json-diagnostic-rendered-ansi"
+    } else {
+        "--message-format=json"
+    }
+}
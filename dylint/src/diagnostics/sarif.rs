@@ -0,0 +1,259 @@
+//! Conversion of collected diagnostics into a [SARIF 2.1.0] log
+//!
+//! [SARIF 2.1.0]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use super::{Library, ToolchainDiagnostic};
+use cargo_metadata::diagnostic::DiagnosticLevel;
+use serde::Serialize;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://github.com/trailofbits/dylint";
+
+#[derive(Debug, Serialize)]
+pub struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: ToolComponent,
+    extensions: Vec<ToolComponent>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    information_uri: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<ReportingDescriptor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<Properties>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    short_description: Message,
+    default_configuration: ReportingConfiguration,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportingConfiguration {
+    level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Properties {
+    toolchain: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    text: String,
+}
+
+// smoelius: Named `SarifResult` to avoid confusion with `anyhow::Result`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule: Option<ReportingDescriptorReference>,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    properties: Properties,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptorReference {
+    id: String,
+    index: usize,
+    tool_component: ToolComponentReference,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolComponentReference {
+    name: String,
+    index: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl Log {
+    /// Builds a log with a single run. Each library becomes a tool extension whose rules are the
+    /// library's lints.
+    #[must_use]
+    pub fn new(libraries: &[Library], diagnostics: &[ToolchainDiagnostic]) -> Self {
+        let extensions = libraries
+            .iter()
+            .map(|library| ToolComponent {
+                name: library.name.clone(),
+                version: None,
+                information_uri: None,
+                rules: library
+                    .lints
+                    .iter()
+                    .map(|lint| ReportingDescriptor {
+                        id: lint.name.clone(),
+                        short_description: Message {
+                            text: lint.description.clone(),
+                        },
+                        default_configuration: ReportingConfiguration {
+                            level: lint_level(&lint.level),
+                        },
+                    })
+                    .collect(),
+                properties: Some(Properties {
+                    toolchain: library.toolchain.clone(),
+                }),
+            })
+            .collect();
+
+        let results = diagnostics
+            .iter()
+            .filter_map(|diagnostic| result(libraries, diagnostic))
+            .collect();
+
+        Self {
+            schema: SCHEMA,
+            version: VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: String::from("dylint"),
+                        version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+                        information_uri: Some(INFORMATION_URI.to_owned()),
+                        rules: Vec::new(),
+                        properties: None,
+                    },
+                    extensions,
+                },
+                results,
+            }],
+        }
+    }
+}
+
+fn result(
+    libraries: &[Library],
+    ToolchainDiagnostic {
+        toolchain,
+        diagnostic,
+    }: &ToolchainDiagnostic,
+) -> Option<SarifResult> {
+    let locations = diagnostic
+        .spans
+        .iter()
+        .filter(|span| span.is_primary)
+        .map(|span| Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation {
+                    uri: span.file_name.replace('\\', "/"),
+                },
+                region: Region {
+                    start_line: span.line_start,
+                    start_column: span.column_start,
+                    end_line: span.line_end,
+                    end_column: span.column_end,
+                },
+            },
+        })
+        .collect::<Vec<_>>();
+
+    // smoelius: Diagnostics without locations are things like "N warnings emitted". They are not
+    // useful to code-scanning tools.
+    if locations.is_empty() {
+        return None;
+    }
+
+    let rule_id = diagnostic.code.as_ref().map(|code| code.code.clone());
+
+    let rule = rule_id.as_ref().and_then(|rule_id| {
+        libraries
+            .iter()
+            .enumerate()
+            .filter(|(_, library)| library.toolchain == *toolchain)
+            .find_map(|(library_index, library)| {
+                let index = library
+                    .lints
+                    .iter()
+                    .position(|lint| lint.name == *rule_id)?;
+                Some(ReportingDescriptorReference {
+                    id: rule_id.clone(),
+                    index,
+                    tool_component: ToolComponentReference {
+                        name: library.name.clone(),
+                        index: library_index,
+                    },
+                })
+            })
+    });
+
+    Some(SarifResult {
+        rule_id,
+        rule,
+        level: match diagnostic.level {
+            DiagnosticLevel::Ice | DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+            _ => "note",
+        },
+        message: Message {
+            text: diagnostic.message.clone(),
+        },
+        locations,
+        properties: Properties {
+            toolchain: toolchain.clone(),
+        },
+    })
+}
+
+fn lint_level(level: &str) -> &'static str {
+    match level {
+        "allow" => "none",
+        "deny" | "forbid" => "error",
+        _ => "warning",
+    }
+}
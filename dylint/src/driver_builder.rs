@@ -1,14 +1,18 @@
-use crate::{error::warn, opts};
+use crate::{NameToolchainMap, error::warn, opts};
 use anyhow::{Context, Result, anyhow, ensure};
 use cargo_metadata::MetadataCommand;
 use dylint_internal::{
-    CommandExt, driver as dylint_driver, env,
-    rustup::{SanitizeEnvironment, toolchain_path},
+    CommandExt, DRIVER_PROTOCOL_VERSION, driver as dylint_driver, env,
+    rustup::{SanitizeEnvironment, installed_toolchains, toolchain_path},
 };
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeSet,
     env::{consts, home_dir},
-    fs::{copy, create_dir_all, rename, write},
+    fs::{
+        copy, create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, rename, write,
+    },
     path::{Path, PathBuf},
 };
 use tempfile::{NamedTempFile, tempdir};
@@ -19,10 +23,23 @@ const README_TXT: &str = "
 This directory contains Rust compiler drivers used by Dylint
 (https://github.com/trailofbits/dylint).
 
-Deleting this directory will cause Dylint to rebuild the drivers
-the next time it needs them, but will have no ill effects.
+Use `cargo dylint driver list` to see the drivers, and
+`cargo dylint driver prune` or `cargo dylint driver clean` to remove
+them. Removing a driver will cause Dylint to rebuild it the next
+time it is needed, but will have no ill effects.
 ";
 
+// smoelius: An imported driver is accompanied by a metadata file, `DRIVER_METADATA_JSON`. The
+// file's presence tells `get` to check that the driver matches the requested toolchain before using
+// it.
+const DRIVER_METADATA_JSON: &str = "dylint-driver.json";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct DriverMetadata {
+    toolchain: String,
+    version: String,
+}
+
 // smoelius: We need `#![feature(rustc_private)]` as it changes `dylib` linking behavior and allows
 // us to link to `rustc_driver`. See: https://github.com/rust-lang/rust/pull/122362
 const MAIN_RS: &str = r"
@@ -45,6 +62,279 @@ pub fn main() -> Result<()> {
     allow(question_mark_in_expression)
 )]
 pub fn get(opts: &opts::Dylint, toolchain: &str) -> Result<PathBuf> {
+    let driver_dir = driver_dir(toolchain)?;
+
+    let driver = driver_dir.join("dylint-driver");
+    if !driver.exists() || is_outdated(opts, toolchain, &driver_dir)? {
+        build(opts, toolchain, &driver_dir)?;
+    }
+
+    Ok(driver)
+}
+
+pub fn run(opts: &opts::Dylint, driver_opts: &opts::Driver) -> Result<()> {
+    match driver_opts {
+        opts::Driver::Build(build_opts) => {
+            let driver = get(opts, &build_opts.toolchain)?;
+            println!("{}", driver.to_string_lossy());
+            Ok(())
+        }
+        opts::Driver::Clean => clean(opts),
+        #[cfg(feature = "package_options")]
+        opts::Driver::Export(export_opts) => export(opts, export_opts),
+        #[cfg(feature = "package_options")]
+        opts::Driver::Import(import_opts) => import(opts, import_opts),
+        opts::Driver::List => list(opts),
+        opts::Driver::Prune(_) => prune(opts),
+    }
+}
+
+fn list(opts: &opts::Dylint) -> Result<()> {
+    let installed = installed_driver_dirs()?;
+
+    let toolchain_width = installed
+        .iter()
+        .map(|(toolchain, _)| toolchain.len())
+        .max()
+        .unwrap_or_default();
+
+    for (toolchain, driver_dir) in &installed {
+        let driver = driver_dir.join("dylint-driver");
+
+        let version = driver_version(toolchain, &driver).map_or_else(
+            |_| "<unknown>".to_owned(),
+            |(_, version)| version.to_string(),
+        );
+
+        let size = driver
+            .metadata()
+            .with_context(|| format!("`metadata` failed for `{}`", driver.to_string_lossy()))?
+            .len();
+
+        let mut notes = Vec::new();
+        if driver_dir.join(DRIVER_METADATA_JSON).exists() {
+            notes.push("imported");
+        }
+        if is_outdated(opts, toolchain, driver_dir)? {
+            notes.push("outdated");
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!("    ({})", notes.join(", "))
+        };
+
+        println!(
+            "{toolchain:<toolchain_width$}    {version:<9}    {:>10}{notes}",
+            format_size(size)
+        );
+    }
+
+    Ok(())
+}
+
+fn prune(opts: &opts::Dylint) -> Result<()> {
+    let installed_toolchains = installed_toolchains()?;
+
+    let name_toolchain_map = NameToolchainMap::new(opts);
+    let inited = name_toolchain_map.get_or_try_init()?;
+    // smoelius: If no libraries were found, every driver would appear unused. More likely, the
+    // command was run from the wrong directory.
+    ensure!(
+        !inited.is_empty(),
+        "No libraries were found. Refusing to prune, as every driver would be removed."
+    );
+    let used_toolchains = inited
+        .values()
+        .flat_map(|toolchain_map| toolchain_map.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    for (toolchain, driver_dir) in installed_driver_dirs()? {
+        let reason = if !installed_toolchains.contains(&toolchain) {
+            "toolchain is not installed"
+        } else if !used_toolchains.contains(&toolchain) {
+            "no discovered library uses the toolchain"
+        } else {
+            continue;
+        };
+
+        remove_driver_dir(&driver_dir)?;
+
+        if !opts.quiet {
+            eprintln!("Removed driver for toolchain `{toolchain}` ({reason})");
+        }
+    }
+
+    Ok(())
+}
+
+fn clean(opts: &opts::Dylint) -> Result<()> {
+    for (toolchain, driver_dir) in installed_driver_dirs()? {
+        remove_driver_dir(&driver_dir)?;
+
+        if !opts.quiet {
+            eprintln!("Removed driver for toolchain `{toolchain}`");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the toolchain and directory of each driver in the driver directory, sorted by toolchain
+fn installed_driver_dirs() -> Result<Vec<(String, PathBuf)>> {
+    let dylint_drivers = dylint_drivers()?;
+
+    let mut driver_dirs = Vec::new();
+
+    for entry in read_dir(&dylint_drivers).with_context(|| {
+        format!(
+            "`read_dir` failed for `{}`",
+            dylint_drivers.to_string_lossy()
+        )
+    })? {
+        let entry = entry.with_context(|| {
+            format!(
+                "`read_dir` failed for `{}`",
+                dylint_drivers.to_string_lossy()
+            )
+        })?;
+        let path = entry.path();
+        if !path.join("dylint-driver").is_file() {
+            continue;
+        }
+        let toolchain = entry.file_name().to_string_lossy().to_string();
+        driver_dirs.push((toolchain, path));
+    }
+
+    driver_dirs.sort();
+
+    Ok(driver_dirs)
+}
+
+fn remove_driver_dir(driver_dir: &Path) -> Result<()> {
+    remove_dir_all(driver_dir).with_context(|| {
+        format!(
+            "`remove_dir_all` failed for `{}`",
+            driver_dir.to_string_lossy()
+        )
+    })
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{size} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(feature = "package_options")]
+fn export(opts: &opts::Dylint, export_opts: &opts::DriverExport) -> Result<()> {
+    let opts::DriverExport { toolchain, archive } = export_opts;
+
+    let driver = get(opts, toolchain)?;
+
+    let (their_toolchain, version) = driver_version(toolchain, &driver)?;
+    ensure!(
+        their_toolchain == *toolchain,
+        "Driver for toolchain `{toolchain}` reports toolchain `{their_toolchain}`"
+    );
+
+    let metadata = serde_json::to_vec_pretty(&DriverMetadata {
+        toolchain: toolchain.clone(),
+        version: version.to_string(),
+    })?;
+
+    let file =
+        std::fs::File::create(archive).with_context(|| format!("Could not create `{archive}`"))?;
+    let mut builder = tar::Builder::new(file);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(metadata.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, DRIVER_METADATA_JSON, metadata.as_slice())
+        .with_context(|| format!("Could not write `{archive}`"))?;
+    builder
+        .append_path_with_name(&driver, "dylint-driver")
+        .with_context(|| format!("Could not write `{archive}`"))?;
+    builder
+        .into_inner()
+        .with_context(|| format!("Could not write `{archive}`"))?;
+
+    Ok(())
+}
+
+#[cfg(feature = "package_options")]
+fn import(opts: &opts::Dylint, import_opts: &opts::DriverImport) -> Result<()> {
+    let opts::DriverImport { archive } = import_opts;
+
+    let tempdir = tempdir().with_context(|| "`tempdir` failed")?;
+
+    let file =
+        std::fs::File::open(archive).with_context(|| format!("Could not open `{archive}`"))?;
+    tar::Archive::new(file)
+        .unpack(tempdir.path())
+        .with_context(|| format!("Could not unpack `{archive}`"))?;
+
+    let metadata = read_metadata(tempdir.path())?
+        .ok_or_else(|| anyhow!("`{archive}` does not contain `{DRIVER_METADATA_JSON}`"))?;
+
+    // smoelius: The toolchain becomes a directory name. So make sure it is just one path component.
+    ensure!(
+        Path::new(&metadata.toolchain).file_name()
+            == Some(std::ffi::OsStr::new(&metadata.toolchain)),
+        "`{archive}` names an invalid toolchain `{}`",
+        metadata.toolchain
+    );
+    Version::parse(&metadata.version).with_context(|| {
+        format!(
+            "`{archive}` names an invalid driver version `{}`",
+            metadata.version
+        )
+    })?;
+
+    let binary = tempdir.path().join("dylint-driver");
+    ensure!(
+        binary.is_file(),
+        "`{archive}` does not contain `dylint-driver`"
+    );
+
+    let driver_dir = driver_dir(&metadata.toolchain)?;
+
+    install(&binary, &driver_dir)?;
+
+    let metadata_json = driver_dir.join(DRIVER_METADATA_JSON);
+    copy(tempdir.path().join(DRIVER_METADATA_JSON), &metadata_json).with_context(|| {
+        format!(
+            "Could not copy metadata to `{}`",
+            metadata_json.to_string_lossy()
+        )
+    })?;
+
+    if !opts.quiet {
+        eprintln!(
+            "Imported driver version {} for toolchain `{}`",
+            metadata.version, metadata.toolchain
+        );
+    }
+
+    Ok(())
+}
+
+fn driver_dir(toolchain: &str) -> Result<PathBuf> {
     let dylint_drivers = dylint_drivers()?;
 
     let driver_dir = dylint_drivers.join(toolchain);
@@ -57,12 +347,7 @@ pub fn get(opts: &opts::Dylint, toolchain: &str) -> Result<PathBuf> {
         })?;
     }
 
-    let driver = driver_dir.join("dylint-driver");
-    if !driver.exists() || is_outdated(opts, toolchain, &driver)? {
-        build(opts, toolchain, &driver_dir)?;
-    }
-
-    Ok(driver)
+    Ok(driver_dir)
 }
 
 fn dylint_drivers() -> Result<PathBuf> {
@@ -89,23 +374,26 @@ fn dylint_drivers() -> Result<PathBuf> {
     }
 }
 
-fn is_outdated(opts: &opts::Dylint, toolchain: &str, driver: &Path) -> Result<bool> {
+fn is_outdated(opts: &opts::Dylint, toolchain: &str, driver_dir: &Path) -> Result<bool> {
     (|| -> Result<bool> {
-        let mut command = dylint_driver(toolchain, driver)?;
-        let output = command.args(["-V"]).logged_output(true)?;
-        let stdout = std::str::from_utf8(&output.stdout)?;
-        let theirs = stdout
-            .trim_end()
-            .rsplit_once(' ')
-            .map(|(_, s)| s)
-            .ok_or_else(|| anyhow!("Could not determine driver version"))?;
-
-        let their_version = Version::parse(theirs)
-            .with_context(|| format!("Could not parse driver version `{theirs}`"))?;
+        let driver = driver_dir.join("dylint-driver");
+
+        let (their_toolchain, their_version) = driver_version(toolchain, &driver)?;
+
+        // smoelius: An imported driver is used only if it is exactly what its metadata claims.
+        if let Some(metadata) = read_metadata(driver_dir)? {
+            ensure!(
+                metadata.toolchain == toolchain
+                    && their_toolchain == toolchain
+                    && metadata.version == their_version.to_string(),
+                "Imported driver (`{their_toolchain} {their_version}`) does not match its metadata \
+                 or toolchain `{toolchain}`"
+            );
+        }
 
         let our_version = Version::parse(env!("CARGO_PKG_VERSION"))?;
 
-        Ok(their_version < our_version)
+        Ok(is_older(&their_version, &our_version))
     })()
     .or_else(|error| {
         warn(opts, &error.to_string());
@@ -113,6 +401,58 @@ fn is_outdated(opts: &opts::Dylint, toolchain: &str, driver: &Path) -> Result<bo
     })
 }
 
+/// Returns true if a driver reporting `their_version` predates `our_version`, or has the same
+/// version but speaks an older protocol. Drivers built before protocol versions were introduced
+/// report no build metadata, and are treated as speaking protocol 0.
+fn is_older(their_version: &Version, our_version: &Version) -> bool {
+    let their_protocol = their_version
+        .build
+        .as_str()
+        .strip_prefix("protocol.")
+        .and_then(|protocol| protocol.parse::<u64>().ok())
+        .unwrap_or_default();
+
+    let their_version = Version::new(
+        their_version.major,
+        their_version.minor,
+        their_version.patch,
+    );
+
+    their_version < *our_version
+        || (their_version == *our_version && their_protocol < DRIVER_PROTOCOL_VERSION)
+}
+
+// smoelius: The driver's `-V` output is `<toolchain> <version>+protocol.<n>`.
+fn driver_version(toolchain: &str, driver: &Path) -> Result<(String, Version)> {
+    let mut command = dylint_driver(toolchain, driver)?;
+    let output = command.args(["-V"]).logged_output(true)?;
+    let stdout = std::str::from_utf8(&output.stdout)?;
+    let (their_toolchain, theirs) = stdout
+        .trim_end()
+        .rsplit_once(' ')
+        .ok_or_else(|| anyhow!("Could not determine driver version"))?;
+
+    let their_version = Version::parse(theirs)
+        .with_context(|| format!("Could not parse driver version `{theirs}`"))?;
+
+    Ok((their_toolchain.to_owned(), their_version))
+}
+
+fn read_metadata(dir: &Path) -> Result<Option<DriverMetadata>> {
+    let path = dir.join(DRIVER_METADATA_JSON);
+    if !path
+        .try_exists()
+        .with_context(|| format!("Could not determine whether `{}` exists", path.display()))?
+    {
+        return Ok(None);
+    }
+    let contents = read_to_string(&path)
+        .with_context(|| format!("`read_to_string` failed for `{}`", path.to_string_lossy()))?;
+    let metadata = serde_json::from_str(&contents)
+        .with_context(|| format!("Could not parse `{}`", path.to_string_lossy()))?;
+    Ok(Some(metadata))
+}
+
 #[cfg_attr(dylint_lib = "supplementary", allow(commented_out_code))]
 fn build(opts: &opts::Dylint, toolchain: &str, driver_dir: &Path) -> Result<()> {
     let tempdir = tempdir().with_context(|| "`tempdir` failed")?;
@@ -144,6 +484,7 @@ fn build(opts: &opts::Dylint, toolchain: &str, driver_dir: &Path) -> Result<()>
 
     dylint_internal::cargo::build(&format!("driver for toolchain `{toolchain}`"))
         .quiet(opts.quiet)
+        .offline(opts.offline())
         .build()
         .sanitize_environment()
         .envs([(env::RUSTFLAGS, rustflags)])
@@ -155,13 +496,31 @@ fn build(opts: &opts::Dylint, toolchain: &str, driver_dir: &Path) -> Result<()>
         .join("debug")
         .join(format!("dylint_driver-{toolchain}{}", consts::EXE_SUFFIX));
 
+    install(binary.as_std_path(), driver_dir)?;
+
+    // smoelius: The driver was just built. So it is no longer an imported one.
+    let metadata_json = driver_dir.join(DRIVER_METADATA_JSON);
+    if metadata_json.exists() {
+        remove_file(&metadata_json).with_context(|| {
+            format!(
+                "`remove_file` failed for `{}`",
+                metadata_json.to_string_lossy()
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+fn install(binary: &Path, driver_dir: &Path) -> Result<()> {
     let named_temp_file =
         NamedTempFile::new_in(driver_dir).with_context(|| "Could not create temporary file")?;
 
     #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
-    copy(&binary, &named_temp_file).with_context(|| {
+    copy(binary, &named_temp_file).with_context(|| {
         format!(
-            "Could not copy `{binary}` to `{}`",
+            "Could not copy `{}` to `{}`",
+            binary.to_string_lossy(),
             named_temp_file.path().to_string_lossy()
         )
     })?;
@@ -243,6 +602,36 @@ components = ["llvm-tools-preview", "rustc-dev"]
 mod test {
     use super::*;
 
+    #[test]
+    fn older_protocols_are_outdated() {
+        let our_version = Version::parse("5.0.0").unwrap();
+        let current = format!("5.0.0+protocol.{DRIVER_PROTOCOL_VERSION}");
+
+        for (their_version, older) in [
+            ("4.1.0", true),
+            ("4.1.0+protocol.1", true),
+            ("5.0.0", true),
+            ("5.0.0+protocol.0", true),
+            (current.as_str(), false),
+            ("5.0.1", false),
+        ] {
+            let their_version = Version::parse(their_version).unwrap();
+            assert_eq!(
+                older,
+                is_older(&their_version, &our_version),
+                "{their_version}"
+            );
+        }
+    }
+
+    #[test]
+    fn format_sizes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(300 * 1024 * 1024), "300.0 MiB");
+    }
+
     // smoelius: `tempdir` is a temporary directory. So there should be no race here.
     #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
     #[test]
@@ -7,27 +7,51 @@
 use anyhow::{Context, Result, anyhow, bail, ensure};
 use cargo_metadata::MetadataCommand;
 use dylint_internal::{
-    CommandExt, driver as dylint_driver, env, parse_path_filename, rustup::SanitizeEnvironment,
+    CommandExt, driver as dylint_driver, env, list, parse_path_filename,
+    rustup::SanitizeEnvironment,
 };
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env::{consts, current_dir},
     ffi::OsStr,
-    fs::OpenOptions,
+    fs::{File, OpenOptions, write},
+    io::{IsTerminal, Write, sink},
     path::{MAIN_SEPARATOR, Path, PathBuf},
-    sync::LazyLock,
+    process::{Command, Output, Stdio},
+    sync::{
+        LazyLock, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
 type Object = serde_json::Map<String, serde_json::Value>;
 
+mod clean;
+
+mod config;
+
 pub mod driver_builder;
 
+mod diagnostics;
+use diagnostics::{
+    baseline::{Baseline, Fingerprinter, Matcher},
+    diff::ChangedLines,
+};
+
 mod error;
 use error::warn;
 #[doc(hidden)]
 pub use error::warn as __warn;
 pub use error::{ColorizedError, ColorizedResult};
 
+#[cfg(feature = "package_options")]
+mod lint_selection;
+
+/// For each toolchain, the lint levels implied by `--lint`. They are appended to the levels from
+/// `dylint.toml`'s `lints` table, and so take precedence over them.
+type LintLevels = BTreeMap<String, Vec<(String, &'static str)>>;
+
 mod name_toolchain_map;
 pub use name_toolchain_map::{Lazy as NameToolchainMap, ToolchainMap};
 use name_toolchain_map::{LazyToolchainMap, MaybeLibrary};
@@ -55,7 +79,7 @@ pub fn run(opts: &opts::Dylint) -> Result<()> {
 
         if matches!(
             opts.operation,
-            opts::Operation::Check(_) | opts::Operation::List(_)
+            opts::Operation::Check(_) | opts::Operation::Config(_) | opts::Operation::List(_)
         ) {
             let lib_sel = opts.library_selection_mut();
 
@@ -83,6 +107,58 @@ pub fn run(opts: &opts::Dylint) -> Result<()> {
         bail!("`--pattern` can be used only with `--git` or `--path`");
     }
 
+    if opts.has_library_selection()
+        && !opts.library_selection().excludes.is_empty()
+        && !opts.git_or_path()
+    {
+        bail!("`--exclude` can be used only with `--git` or `--path`");
+    }
+
+    if let opts::Operation::Check(check_opts) = &opts.operation {
+        ensure!(
+            check_opts.output_file.is_none()
+                || check_opts.output_format != opts::OutputFormat::Human,
+            "`--output-file` requires an `--output-format` other than `human`"
+        );
+        ensure!(check_opts.jobs != Some(0), "`--jobs` must be at least 1");
+        // smoelius: Each `cargo fix` command rewrites the same source files. So running them
+        // concurrently could cause one command's fixes to clobber another's.
+        ensure!(
+            !check_opts.fix || check_opts.jobs.unwrap_or(1) <= 1,
+            "`--fix` cannot be used with `--jobs` greater than 1"
+        );
+        ensure!(
+            check_opts.baseline.is_none() || check_opts.write_baseline.is_none(),
+            "`--baseline` and `--write-baseline` cannot be used together"
+        );
+        if check_opts.collects_diagnostics() {
+            ensure!(
+                !check_opts.fix,
+                "`--fix` cannot be used with `--baseline`, `--diff-base`, `--output-format`, or \
+                 `--write-baseline`"
+            );
+            ensure!(
+                opts.pipe_stdout.is_none(),
+                "`--pipe-stdout` cannot be used with `--baseline`, `--diff-base`, \
+                 `--output-format`, or `--write-baseline`"
+            );
+        }
+    }
+
+    if matches!(opts.operation, opts::Operation::Update(_)) {
+        ensure!(
+            !opts.git_or_path(),
+            "`update` cannot be used with `--git` or `--path`"
+        );
+    }
+
+    if let opts::Operation::Config(config_opts) = &opts.operation {
+        ensure!(
+            config_opts.schema != config_opts.validate,
+            "Exactly one of `--schema` or `--validate` must be used"
+        );
+    }
+
     if opts.pipe_stderr.is_some() {
         warn(&opts, "`--pipe-stderr` is experimental");
     }
@@ -92,10 +168,13 @@ pub fn run(opts: &opts::Dylint) -> Result<()> {
     }
 
     match &opts.operation {
-        opts::Operation::Check(_) | opts::Operation::List(_) => {
+        opts::Operation::Check(_) | opts::Operation::Config(_) | opts::Operation::List(_) => {
             let name_toolchain_map = NameToolchainMap::new(&opts);
             run_with_name_toolchain_map(&opts, &name_toolchain_map)
         }
+        opts::Operation::Clean(clean_opts) => clean::clean(&opts, clean_opts),
+        opts::Operation::Driver(driver_opts) => driver_builder::run(&opts, driver_opts),
+        opts::Operation::Update(_) => update(&opts),
         #[cfg(feature = "package_options")]
         opts::Operation::New(new_opts) => package_options::new_package(&opts, new_opts),
         #[cfg(feature = "package_options")]
@@ -111,10 +190,14 @@ fn run_with_name_toolchain_map(
 ) -> Result<()> {
     let lib_sel = opts.library_selection();
 
-    if lib_sel.libs.is_empty() && lib_sel.lib_paths.is_empty() && !lib_sel.all {
-        if matches!(opts.operation, opts::Operation::List(_)) {
+    if lib_sel.libs.is_empty()
+        && lib_sel.lib_paths.is_empty()
+        && lib_sel.lints.is_empty()
+        && !lib_sel.all
+    {
+        if let opts::Operation::List(list_opts) = &opts.operation {
             warn_if_empty(opts, name_toolchain_map)?;
-            return list_libs(name_toolchain_map);
+            return list_libs(list_opts, name_toolchain_map);
         }
 
         warn(opts, "Nothing to do. Did you forget `--all`?");
@@ -134,14 +217,46 @@ fn run_with_name_toolchain_map(
         assert!(name_toolchain_map_is_empty || !lib_sel.all);
     }
 
+    #[cfg(feature = "package_options")]
+    let (resolved, lint_levels) = if lib_sel.lints.is_empty() {
+        (resolved, LintLevels::new())
+    } else {
+        lint_selection::select(opts, resolved)?
+    };
+    #[cfg(not(feature = "package_options"))]
+    let (resolved, lint_levels) = {
+        ensure!(
+            lib_sel.lints.is_empty(),
+            "`--lint` requires the `package_options` feature"
+        );
+        (resolved, LintLevels::new())
+    };
+
     match &opts.operation {
-        opts::Operation::Check(check_opts) => check_or_fix(opts, check_opts, &resolved),
-        opts::Operation::List(_) => list_lints(opts, &resolved),
+        opts::Operation::Check(check_opts) => {
+            check_or_fix(opts, check_opts, &resolved, &lint_levels)
+        }
+        opts::Operation::Config(config_opts) => {
+            if config_opts.schema {
+                config::print_schema(opts, &resolved)
+            } else {
+                config::validate(opts, &resolved)
+            }
+        }
+        opts::Operation::List(list_opts) => list_lints(opts, list_opts, &resolved),
         #[allow(unreachable_patterns)]
         _ => unreachable!(),
     }
 }
 
+// smoelius: Discovering the libraries re-resolves the git-sourced ones and rewrites `dylint.lock`.
+// See `library_packages::library_packages`.
+fn update(opts: &opts::Dylint) -> Result<()> {
+    let name_toolchain_map = NameToolchainMap::new(opts);
+    warn_if_empty(opts, &name_toolchain_map)?;
+    Ok(())
+}
+
 fn warn_if_empty(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result<bool> {
     let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
 
@@ -153,9 +268,49 @@ fn warn_if_empty(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) ->
     })
 }
 
-fn list_libs(name_toolchain_map: &NameToolchainMap) -> Result<()> {
+/// A library as listed by `cargo dylint list --format json`
+#[derive(Serialize)]
+struct LibraryRecord {
+    name: String,
+    toolchain: String,
+    location: String,
+    built: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lints: Option<Vec<list::Lint>>,
+}
+
+impl LibraryRecord {
+    fn new(name: &str, toolchain: &str, path: &Path, lints: Option<Vec<list::Lint>>) -> Self {
+        Self {
+            name: name.to_owned(),
+            toolchain: toolchain.to_owned(),
+            location: path.to_string_lossy().to_string(),
+            built: path.exists(),
+            lints,
+        }
+    }
+}
+
+fn list_libs(list_opts: &opts::List, name_toolchain_map: &NameToolchainMap) -> Result<()> {
     let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
 
+    if list_opts.format == opts::ListFormat::Json {
+        let mut records = Vec::new();
+        for (name, toolchain_map) in name_toolchain_map {
+            for (toolchain, maybe_libraries) in toolchain_map {
+                for maybe_library in maybe_libraries {
+                    records.push(LibraryRecord::new(
+                        name,
+                        toolchain,
+                        &maybe_library.path(),
+                        None,
+                    ));
+                }
+            }
+        }
+        return print_json(&records);
+    }
+
     let name_width = name_toolchain_map
         .keys()
         .map(String::len)
@@ -173,7 +328,7 @@ fn list_libs(name_toolchain_map: &NameToolchainMap) -> Result<()> {
         for (toolchain, maybe_libraries) in toolchain_map {
             for maybe_library in maybe_libraries {
                 let location = display_location(&maybe_library.path())?;
-                println!("{name:<name_width$}  {toolchain:<toolchain_width$}  {location}",);
+                println!("{name:<name_width$}  {toolchain:<toolchain_width$}  {location}");
             }
         }
     }
@@ -190,7 +345,11 @@ fn resolve(opts: &opts::Dylint, name_toolchain_map: &NameToolchainMap) -> Result
 
     let mut toolchain_map = ToolchainMap::new();
 
-    if lib_sel.all {
+    // smoelius: `--lint` alone selects from every discovered library. Note that this means every
+    // discovered library is built, because a library's lints cannot be listed until it is built.
+    if lib_sel.all
+        || (!lib_sel.lints.is_empty() && lib_sel.libs.is_empty() && lib_sel.lib_paths.is_empty())
+    {
         let name_toolchain_map = name_toolchain_map.get_or_try_init()?;
 
         for other in name_toolchain_map.values() {
@@ -310,105 +469,110 @@ fn check_or_fix(
     opts: &opts::Dylint,
     check_opts: &opts::Check,
     resolved: &ToolchainMap,
+    lint_levels: &LintLevels,
 ) -> Result<()> {
+    let mut collector = if check_opts.collects_diagnostics() {
+        Some(collector(opts, check_opts, resolved)?)
+    } else {
+        None
+    };
+
     let clippy_disable_docs_links = clippy_disable_docs_links()?;
 
-    let mut failures = Vec::new();
+    let jobs = check_opts.jobs.unwrap_or(1);
+
+    // smoelius: When checking with multiple toolchains concurrently, each command's output is
+    // buffered so that it can be replayed in a deterministic order.
+    let mut finished = if jobs > 1 {
+        let commands = resolved
+            .iter()
+            .map(|(toolchain, paths)| {
+                check_command(
+                    opts,
+                    check_opts,
+                    toolchain,
+                    paths,
+                    lint_levels,
+                    &clippy_disable_docs_links,
+                    collector.is_some(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // smoelius: When diagnostics are collected, a failed command does not necessarily mean
+        // failure. So the remaining commands are not skipped in that case.
+        let stop_on_failure = !check_opts.keep_going && collector.is_none();
+        Some(run_concurrently(commands, jobs, stop_on_failure))
+    } else {
+        None
+    };
 
-    for (toolchain, paths) in resolved {
-        let target_dir = target_dir(opts, toolchain)?;
-        let target_dir_str = target_dir.to_string_lossy();
-        let driver = driver_builder::get(opts, toolchain)?;
-        let dylint_libs = serde_json::to_string(&paths)?;
-        #[cfg(not(__library_packages))]
-        let dylint_metadata = None;
-        #[cfg(__library_packages)]
-        let dylint_metadata = library_packages::dylint_metadata(opts)?;
-        let dylint_metadata_str = dylint_metadata
-            .map(|object: &Object| serde_json::Value::from(object.clone()))
-            .unwrap_or_default()
-            .to_string();
-        let description = format!("with toolchain `{toolchain}`");
-        let mut command = if check_opts.fix {
-            dylint_internal::cargo::fix(&description)
+    let mut failures = Vec::new();
+    let mut error = None;
+
+    for (index, (toolchain, paths)) in resolved.iter().enumerate() {
+        let result = if let Some(finished) = &mut finished {
+            let (command, output) = &mut finished[index];
+            // smoelius: A command is skipped only after a preceding command failed. So the loop
+            // should have exited before reaching a skipped command.
+            let Some(output) = output.take() else {
+                break;
+            };
+            output.and_then(|output| {
+                replay(
+                    opts,
+                    check_opts,
+                    collector.as_mut(),
+                    command,
+                    toolchain,
+                    &output,
+                )
+            })
         } else {
-            dylint_internal::cargo::check(&description)
-        }
-        .build();
-        let mut args = vec!["--target-dir", &target_dir_str];
-        if let Some(path) = &check_opts.lib_sel.manifest_path {
-            args.extend(["--manifest-path", path]);
-        }
-        for spec in &check_opts.packages {
-            args.extend(["-p", spec]);
-        }
-        if check_opts.workspace {
-            args.extend(["--workspace"]);
-        }
-        args.extend(check_opts.args.iter().map(String::as_str));
-
-        // smoelius: Set CLIPPY_DISABLE_DOCS_LINKS to prevent lints from accidentally linking to the
-        // Clippy repository. But set it to the JSON-encoded original value so that the Clippy
-        // library can unset the variable.
-        // smoelius: This doesn't work if another library is loaded alongside Clippy.
-        // smoelius: This was fixed in `clippy_utils`:
-        // https://github.com/rust-lang/rust-clippy/commit/1a206fc4abae0b57a3f393481367cf3efca23586
-        // But I am going to continue to set CLIPPY_DISABLE_DOCS_LINKS because it doesn't seem to
-        // hurt and it provides a small amount of backward compatibility.
-        command
-            .sanitize_environment()
-            .envs([
-                (
-                    env::CLIPPY_DISABLE_DOCS_LINKS,
-                    clippy_disable_docs_links.as_str(),
-                ),
-                (env::DYLINT_LIBS, &dylint_libs),
-                (env::DYLINT_METADATA, &dylint_metadata_str),
-                (
-                    env::DYLINT_NO_DEPS,
-                    if check_opts.no_deps { "1" } else { "0" },
-                ),
-                (env::RUSTC_WORKSPACE_WRAPPER, &*driver.to_string_lossy()),
-                (env::RUSTUP_TOOLCHAIN, toolchain),
-            ])
-            .args(args);
-
-        // smoelius:: See: https://github.com/rust-lang/rustup/pull/3703 and
-        // https://github.com/rust-lang/rustup/issues/3825
-        #[cfg(windows)]
-        {
-            let new_path = dylint_internal::prepend_toolchain_path(toolchain)?;
-            command.envs(vec![(crate::env::PATH, new_path)]);
-        }
-
-        if let Some(stderr_path) = &opts.pipe_stderr {
-            let file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(stderr_path)
-                .with_context(|| format!("Failed to open `{stderr_path}` for stderr usage"))?;
-            command.stderr(file);
-        }
-
-        if let Some(stdout_path) = &opts.pipe_stdout {
-            let file = OpenOptions::new()
-                .append(true)
-                .create(true)
-                .open(stdout_path)
-                .with_context(|| format!("Failed to open `{stdout_path}` for stdout usage"))?;
-            command.stdout(file);
-        }
-
-        let result = command.success();
+            let mut command = check_command(
+                opts,
+                check_opts,
+                toolchain,
+                paths,
+                lint_levels,
+                &clippy_disable_docs_links,
+                collector.is_some(),
+            )?;
+            run_check(
+                opts,
+                check_opts,
+                collector.as_mut(),
+                &mut command,
+                toolchain,
+            )
+        };
         if result.is_err() {
             if !check_opts.keep_going {
-                return result
-                    .with_context(|| format!("Compilation failed with toolchain `{toolchain}`"));
+                // smoelius: Don't return just yet. The diagnostics collected so far should still
+                // be reported.
+                error =
+                    Some(result.with_context(|| {
+                        format!("Compilation failed with toolchain `{toolchain}`")
+                    }));
+                break;
             }
             failures.push(toolchain);
         }
     }
 
+    let new_findings = collector
+        .map(|collector| report(opts, check_opts, collector))
+        .transpose()?
+        .unwrap_or_default();
+
+    if let Some(error) = error {
+        return error;
+    }
+
+    ensure!(
+        new_findings == 0,
+        "Found {new_findings} finding(s) not in the baseline"
+    );
+
     if failures.is_empty() {
         Ok(())
     } else {
@@ -418,39 +582,456 @@ fn check_or_fix(
     }
 }
 
-fn list_lints(opts: &opts::Dylint, resolved: &ToolchainMap) -> Result<()> {
+fn check_command(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    toolchain: &str,
+    paths: &BTreeSet<PathBuf>,
+    lint_levels: &LintLevels,
+    clippy_disable_docs_links: &str,
+    collect_diagnostics: bool,
+) -> Result<Command> {
+    let target_dir = target_dir(opts, toolchain)?;
+    let target_dir_str = target_dir.to_string_lossy();
+    let driver = driver_builder::get(opts, toolchain)?;
+    let dylint_libs = serde_json::to_string(&paths)?;
+    #[cfg(not(__library_packages))]
+    let dylint_metadata = None;
+    #[cfg(__library_packages)]
+    let dylint_metadata = library_packages::dylint_metadata(opts)?;
+    let dylint_metadata_str = dylint_metadata
+        .map(|object: &Object| serde_json::Value::from(object.clone()))
+        .unwrap_or_default()
+        .to_string();
+    #[cfg(not(__library_packages))]
+    let mut dylint_lints = Vec::<(String, &str)>::new();
+    #[cfg(__library_packages)]
+    let mut dylint_lints = library_packages::dylint_toml_lints(opts)?;
+    if let Some(levels) = lint_levels.get(toolchain) {
+        dylint_lints.extend(levels.iter().cloned());
+    }
+    let dylint_lints_str = serde_json::to_string(&dylint_lints)?;
+    let description = format!("with toolchain `{toolchain}`");
+    let mut command = if check_opts.fix {
+        dylint_internal::cargo::fix(&description)
+    } else {
+        dylint_internal::cargo::check(&description)
+    }
+    .offline(opts.offline())
+    .build();
+    let mut args = vec!["--target-dir", &target_dir_str];
+    if let Some(path) = &check_opts.lib_sel.manifest_path {
+        args.extend(["--manifest-path", path]);
+    }
+    for spec in &check_opts.packages {
+        args.extend(["-p", spec]);
+    }
+    if check_opts.workspace {
+        args.extend(["--workspace"]);
+    }
+    if collect_diagnostics {
+        args.push(diagnostics::message_format_arg(use_ansi(opts, check_opts)));
+    }
+    args.extend(check_opts.args.iter().map(String::as_str));
+
+    // smoelius: Set CLIPPY_DISABLE_DOCS_LINKS to prevent lints from accidentally linking to the
+    // Clippy repository. But set it to the JSON-encoded original value so that the Clippy
+    // library can unset the variable.
+    // smoelius: This doesn't work if another library is loaded alongside Clippy.
+    // smoelius: This was fixed in `clippy_utils`:
+    // https://github.com/rust-lang/rust-clippy/commit/1a206fc4abae0b57a3f393481367cf3efca23586
+    // But I am going to continue to set CLIPPY_DISABLE_DOCS_LINKS because it doesn't seem to
+    // hurt and it provides a small amount of backward compatibility.
+    command
+        .sanitize_environment()
+        .envs([
+            (env::CLIPPY_DISABLE_DOCS_LINKS, clippy_disable_docs_links),
+            (env::DYLINT_LIBS, &dylint_libs),
+            (env::DYLINT_LINTS, &dylint_lints_str),
+            (env::DYLINT_METADATA, &dylint_metadata_str),
+            (
+                env::DYLINT_NO_DEPS,
+                if check_opts.no_deps { "1" } else { "0" },
+            ),
+            (env::RUSTC_WORKSPACE_WRAPPER, &*driver.to_string_lossy()),
+            (env::RUSTUP_TOOLCHAIN, toolchain),
+        ])
+        .args(args);
+
+    // smoelius:: See: https://github.com/rust-lang/rustup/pull/3703 and
+    // https://github.com/rust-lang/rustup/issues/3825
+    #[cfg(windows)]
+    {
+        let new_path = dylint_internal::prepend_toolchain_path(toolchain)?;
+        command.envs(vec![(crate::env::PATH, new_path)]);
+    }
+
+    Ok(command)
+}
+
+/// Returns true if rendered diagnostics will be written to a terminal, and thus should contain ANSI
+/// color codes.
+fn use_ansi(opts: &opts::Dylint, check_opts: &opts::Check) -> bool {
+    if check_opts.output_format == opts::OutputFormat::Combined {
+        check_opts.output_file.is_none() && std::io::stdout().is_terminal()
+    } else {
+        opts.pipe_stderr.is_none() && std::io::stderr().is_terminal()
+    }
+}
+
+type Finished = (Command, Option<Result<Output>>);
+
+/// Runs `commands` using up to `jobs` threads, capturing their output. The results are returned in
+/// the same order as `commands`. A command's output is `None` if the command was skipped because
+/// `stop_on_failure` was set and another command failed.
+fn run_concurrently(commands: Vec<Command>, jobs: usize, stop_on_failure: bool) -> Vec<Finished> {
+    let stop = AtomicBool::new(false);
+    let queue = Mutex::new(commands.into_iter().enumerate());
+    let finished = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let Some((index, mut command)) =
+                        queue.lock().unwrap_or_else(PoisonError::into_inner).next()
+                    else {
+                        break;
+                    };
+                    let output = if stop.load(Ordering::SeqCst) {
+                        None
+                    } else {
+                        let output = command
+                            .stdout(Stdio::piped())
+                            .stderr(Stdio::piped())
+                            .logged_output(false);
+                        if stop_on_failure
+                            && !output.as_ref().is_ok_and(|output| output.status.success())
+                        {
+                            stop.store(true, Ordering::SeqCst);
+                        }
+                        Some(output)
+                    };
+                    finished
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push((index, command, output));
+                }
+            });
+        }
+    });
+
+    let mut finished = finished
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner);
+    finished.sort_by_key(|&(index, _, _)| index);
+    finished
+        .into_iter()
+        .map(|(_, command, output)| (command, output))
+        .collect()
+}
+
+/// Runs a check command directly, piping its output and collecting diagnostics if requested.
+fn run_check(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    collector: Option<&mut diagnostics::Collector>,
+    command: &mut Command,
+    toolchain: &str,
+) -> Result<()> {
+    if let Some(stderr_path) = &opts.pipe_stderr {
+        let file = open_append(stderr_path, "stderr")?;
+        command.stderr(file);
+    } else if collector.is_some() {
+        command.stderr(Stdio::inherit());
+    }
+
+    if let Some(stdout_path) = &opts.pipe_stdout {
+        let file = open_append(stdout_path, "stdout")?;
+        command.stdout(file);
+    }
+
+    if let Some(collector) = collector {
+        let mut rendered = rendered_writer(opts, check_opts)?;
+        collector.collect(command, toolchain, &mut *rendered)
+    } else {
+        command.success()
+    }
+}
+
+/// Writes the buffered output of a command run by [`run_concurrently`] to where it would have gone
+/// had the command been run directly.
+fn replay(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    collector: Option<&mut diagnostics::Collector>,
+    command: &Command,
+    toolchain: &str,
+    output: &Output,
+) -> Result<()> {
+    stderr_writer(opts)?.write_all(&output.stderr)?;
+
+    if let Some(collector) = collector {
+        let mut rendered = rendered_writer(opts, check_opts)?;
+        return collector.process(command, toolchain, output, &mut *rendered);
+    }
+
+    let mut stdout: Box<dyn Write> = if let Some(stdout_path) = &opts.pipe_stdout {
+        Box::new(open_append(stdout_path, "stdout")?)
+    } else {
+        Box::new(std::io::stdout())
+    };
+    stdout.write_all(&output.stdout)?;
+
+    ensure!(output.status.success(), "command failed: {command:?}");
+
+    Ok(())
+}
+
+// smoelius: `cargo check` writes rendered diagnostics to stdout when passed
+// `--message-format=json`. Write them to where stderr would normally go, unless they are to be
+// combined into one report.
+fn rendered_writer(opts: &opts::Dylint, check_opts: &opts::Check) -> Result<Box<dyn Write>> {
+    if check_opts.output_format == opts::OutputFormat::Combined {
+        Ok(Box::new(sink()))
+    } else {
+        stderr_writer(opts)
+    }
+}
+
+fn stderr_writer(opts: &opts::Dylint) -> Result<Box<dyn Write>> {
+    if let Some(stderr_path) = &opts.pipe_stderr {
+        Ok(Box::new(open_append(stderr_path, "stderr")?))
+    } else {
+        Ok(Box::new(std::io::stderr()))
+    }
+}
+
+fn list_lints(opts: &opts::Dylint, list_opts: &opts::List, resolved: &ToolchainMap) -> Result<()> {
+    let mut records = Vec::new();
+
     for (toolchain, paths) in resolved {
         for path in paths {
-            let driver = driver_builder::get(opts, toolchain)?;
-            let dylint_libs = serde_json::to_string(&[path])?;
             let (name, _) =
                 parse_path_filename(path).ok_or_else(|| anyhow!("Could not parse path"))?;
 
-            print!("{name}");
-            if resolved.keys().len() >= 2 {
-                print!("@{toolchain}");
-            }
-            if paths.len() >= 2 {
-                let location = display_location(path)?;
-                print!(" ({location})");
+            let lints = library_lints(opts, toolchain, path)?;
+
+            match list_opts.format {
+                opts::ListFormat::Text => {
+                    print!("{name}");
+                    if resolved.keys().len() >= 2 {
+                        print!("@{toolchain}");
+                    }
+                    if paths.len() >= 2 {
+                        let location = display_location(path)?;
+                        print!(" ({location})");
+                    }
+                    println!();
+
+                    print_lints(&lints);
+
+                    println!();
+                }
+                opts::ListFormat::Json => {
+                    records.push(LibraryRecord::new(&name, toolchain, path, Some(lints)));
+                }
             }
-            println!();
-
-            // smoelius: `-W help` is the normal way to list lints, so we can be sure it
-            // gets the lints loaded. However, we don't actually use it to list the lints.
-            let mut command = dylint_driver(toolchain, &driver)?;
-            command
-                .envs([
-                    (env::DYLINT_LIBS, dylint_libs.as_str()),
-                    (env::DYLINT_LIST, "1"),
-                ])
-                .args(["rustc", "-W", "help"])
-                .success()?;
-
-            println!();
         }
     }
 
+    if list_opts.format == opts::ListFormat::Json {
+        print_json(&records)?;
+    }
+
+    Ok(())
+}
+
+fn collector(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    resolved: &ToolchainMap,
+) -> Result<diagnostics::Collector> {
+    let libraries = resolved
+        .iter()
+        .flat_map(|(toolchain, paths)| paths.iter().map(move |path| (toolchain, path)))
+        .map(|(toolchain, path)| {
+            let (name, _) =
+                parse_path_filename(path).ok_or_else(|| anyhow!("Could not parse path"))?;
+            let lints = library_lints(opts, toolchain, path)?;
+            Ok(diagnostics::Library {
+                name,
+                toolchain: toolchain.clone(),
+                lints,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let workspace_root = workspace_root(opts)?;
+
+    let mut collector = diagnostics::Collector::new(libraries);
+
+    if check_opts.baseline.is_some() || check_opts.write_baseline.is_some() {
+        let lints = collector
+            .libraries
+            .iter()
+            .flat_map(|library| library.lints.iter().map(|lint| lint.name.clone()));
+        collector.fingerprinter = Some(Fingerprinter::new(&workspace_root, lints));
+    }
+
+    if let Some(path) = &check_opts.baseline {
+        collector.matcher = Some(Matcher::new(Baseline::read(path)?));
+    }
+
+    if let Some(refname) = &check_opts.diff_base {
+        collector.changed_lines = Some(ChangedLines::new(&workspace_root, refname)?);
+    }
+
+    Ok(collector)
+}
+
+/// Writes any reports requested by `check_opts`. Returns the number of findings that are not in the
+/// baseline, if one was given, or zero otherwise.
+fn report(
+    opts: &opts::Dylint,
+    check_opts: &opts::Check,
+    collector: diagnostics::Collector,
+) -> Result<usize> {
+    let diagnostics::Collector {
+        libraries,
+        diagnostics,
+        findings,
+        fingerprinter: _,
+        matcher,
+        changed_lines: _,
+    } = collector;
+
+    match check_opts.output_format {
+        opts::OutputFormat::Human => {}
+        opts::OutputFormat::Combined => {
+            let mut out: Box<dyn Write> = if let Some(path) = &check_opts.output_file {
+                Box::new(File::create(path).with_context(|| format!("Could not create `{path}`"))?)
+            } else {
+                Box::new(std::io::stdout())
+            };
+            diagnostics::combined::render(&libraries, &diagnostics, &mut *out)?;
+        }
+        opts::OutputFormat::Sarif => {
+            let log = diagnostics::sarif::Log::new(&libraries, &diagnostics);
+            write_report(check_opts.output_file.as_deref(), &log)?;
+        }
+    }
+
+    if let Some(path) = &check_opts.write_baseline {
+        write_report(Some(path), &Baseline::new(findings))?;
+        return Ok(0);
+    }
+
+    let Some(matcher) = matcher else {
+        return Ok(0);
+    };
+
+    let unmatched = matcher.unmatched();
+    #[allow(clippy::format_collect)]
+    if !unmatched.is_empty() {
+        let entries = unmatched
+            .iter()
+            .map(|fingerprint| format!("\n    {fingerprint}"))
+            .collect::<String>();
+        warn(
+            opts,
+            &format!("The following baseline entries no longer occur and can be removed:{entries}"),
+        );
+    }
+
+    Ok(findings.len())
+}
+
+fn open_append(path: &str, usage: &str) -> Result<File> {
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .with_context(|| format!("Failed to open `{path}` for {usage} usage"))
+}
+
+/// Writes `report` as JSON to `output_file`, or to standard output if `output_file` is `None`.
+fn write_report(output_file: Option<&str>, report: &impl Serialize) -> Result<()> {
+    let Some(path) = output_file else {
+        return print_json(report);
+    };
+    let json = serde_json::to_string_pretty(report)?;
+    write(path, json).with_context(|| format!("Could not write to `{path}`"))
+}
+
+/// Asks the driver for the lints registered by the library at `path`.
+fn library_lints(opts: &opts::Dylint, toolchain: &str, path: &Path) -> Result<Vec<list::Lint>> {
+    let driver = driver_builder::get(opts, toolchain)?;
+    let dylint_libs = serde_json::to_string(&[path])?;
+
+    // smoelius: `-W help` is the normal way to list lints, so we can be sure it
+    // gets the lints loaded. However, we don't actually use it to list the lints.
+    let mut command = dylint_driver(toolchain, &driver)?;
+    let output = command
+        .envs([
+            (env::DYLINT_LIBS, dylint_libs.as_str()),
+            (env::DYLINT_LIST, "1"),
+        ])
+        .args(["rustc", "-W", "help"])
+        .stderr(Stdio::inherit())
+        .logged_output(true)?;
+
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Could not parse lints listed for `{}`", path.display()))
+}
+
+fn print_lints(lints: &[list::Lint]) {
+    let mut groups = BTreeMap::<&str, Vec<&str>>::new();
+    for lint in lints {
+        for group in &lint.groups {
+            groups.entry(group).or_default().push(&lint.name);
+        }
+    }
+
+    let name_width = lints
+        .iter()
+        .map(|lint| lint.name.as_str())
+        .chain(groups.keys().copied())
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+
+    let level_width = lints
+        .iter()
+        .map(|lint| lint.level.len())
+        .chain((!groups.is_empty()).then_some("group".len()))
+        .max()
+        .unwrap_or_default();
+
+    for list::Lint {
+        name,
+        level,
+        description,
+        ..
+    } in lints
+    {
+        println!("    {name:<name_width$}    {level:<level_width$}    {description}");
+    }
+
+    // smoelius: A group is listed with the library whose lints it contains. A composite library's
+    // group thus appears once, after the group's lints.
+    for (group, names) in groups {
+        println!(
+            "    {group:<name_width$}    {:<level_width$}    {}",
+            "group",
+            names.join(", ")
+        );
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    println!("{json}");
     Ok(())
 }
 
@@ -474,17 +1055,27 @@ fn clippy_disable_docs_links() -> Result<String> {
     serde_json::to_string(&val).map_err(Into::into)
 }
 
+fn workspace_root(opts: &opts::Dylint) -> Result<PathBuf> {
+    let mut command = MetadataCommand::new();
+    if let Some(path) = &opts.library_selection().manifest_path {
+        command.manifest_path(path);
+    }
+    let metadata = command.no_deps().exec()?;
+    Ok(metadata.workspace_root.into_std_path_buf())
+}
+
 fn target_dir(opts: &opts::Dylint, toolchain: &str) -> Result<PathBuf> {
+    target_dirs(opts).map(|target_dirs| target_dirs.join(toolchain))
+}
+
+/// Returns the directory containing the per-toolchain target directories used for checking
+fn target_dirs(opts: &opts::Dylint) -> Result<PathBuf> {
     let mut command = MetadataCommand::new();
     if let Some(path) = &opts.library_selection().manifest_path {
         command.manifest_path(path);
     }
     let metadata = command.no_deps().exec()?;
-    Ok(metadata
-        .target_directory
-        .join("dylint/target")
-        .join(toolchain)
-        .into())
+    Ok(metadata.target_directory.join("dylint/target").into())
 }
 
 #[allow(clippy::unwrap_used)]
@@ -543,6 +1134,102 @@ mod test {
         run_with_name_toolchain_map(&opts, &name_toolchain_map).unwrap();
     }
 
+    #[cfg_attr(dylint_lib = "general", allow(non_thread_safe_call_in_test))]
+    #[test]
+    fn multiple_libraries_multiple_toolchains_concurrently() {
+        let _lock = MUTEX.lock().unwrap();
+
+        let name_toolchain_map = name_toolchain_map();
+
+        let opts = opts::Dylint {
+            operation: opts::Operation::Check(opts::Check {
+                lib_sel: opts::LibrarySelection {
+                    libs: vec![
+                        "question_mark_in_expression".to_owned(),
+                        "straggler".to_owned(),
+                    ],
+                    ..Default::default()
+                },
+                jobs: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        run_with_name_toolchain_map(&opts, &name_toolchain_map).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_concurrently_preserves_order() {
+        // smoelius: Earlier commands sleep longer, so they finish after later ones.
+        let commands = (0..4)
+            .map(|i| {
+                let mut command = Command::new("sh");
+                command.args(["-c", &format!("sleep 0.{}; echo {i}", 4 - i)]);
+                command
+            })
+            .collect();
+
+        let finished = run_concurrently(commands, 4, true);
+
+        let stdouts = finished
+            .into_iter()
+            .map(|(_, output)| String::from_utf8(output.unwrap().unwrap().stdout).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(["0\n", "1\n", "2\n", "3\n"], stdouts.as_slice());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_concurrently_stop_on_failure() {
+        let commands = || {
+            ["exit 1", "echo 1", "echo 2"]
+                .into_iter()
+                .map(|script| {
+                    let mut command = Command::new("sh");
+                    command.args(["-c", script]);
+                    command
+                })
+                .collect()
+        };
+
+        // smoelius: With one job, the commands run in order. So the first command's failure is
+        // observed before the others start.
+        let finished = run_concurrently(commands(), 1, true);
+        assert!(
+            !finished[0]
+                .1
+                .as_ref()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .status
+                .success()
+        );
+        assert!(finished[1].1.is_none());
+        assert!(finished[2].1.is_none());
+
+        // smoelius: This is how `--keep-going` runs the commands.
+        let finished = run_concurrently(commands(), 1, false);
+        assert!(
+            !finished[0]
+                .1
+                .as_ref()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .status
+                .success()
+        );
+        for (i, (_, output)) in finished.into_iter().enumerate().skip(1) {
+            assert_eq!(
+                format!("{i}\n"),
+                String::from_utf8(output.unwrap().unwrap().stdout).unwrap()
+            );
+        }
+    }
+
     // smoelius: Check that loading multiple libraries with the same Rust toolchain works. At one
     // point, I was getting this error from `libloading`:
     //
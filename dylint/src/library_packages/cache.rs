@@ -0,0 +1,264 @@
+//! A persistent cache of what discovery learns about each library package directory: the package's
+//! name, version, `cdylib` name, and active toolchain. Computing these requires running `cargo
+//! metadata` and `rustup show active-toolchain`, which dominate Dylint's start up time.
+//!
+//! An entry is used only if the directory's `Cargo.toml` and governing `rust-toolchain` file have
+//! the same modification times as when the entry was created, and, for git-sourced libraries, the
+//! checkout is at the same commit.
+//!
+//! A package directory that is not governed by a `rust-toolchain` file, or that is governed by a
+//! `rustup override`, is never cached. Its active toolchain depends on things the cache cannot
+//! observe (e.g., `rustup default`), so discovery runs `cargo metadata` and `rustup show
+//! active-toolchain` for it every time. (`RUSTUP_TOOLCHAIN` does not matter, because discovery
+//! removes it from `rustup`'s environment.)
+
+use anyhow::{Context, Result};
+use cargo_metadata::Metadata;
+use dylint_internal::home::rustup_home;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, metadata, read_to_string, write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+const VERSION: u32 = 1;
+
+const RUST_TOOLCHAIN_FILES: [&str; 2] = ["rust-toolchain", "rust-toolchain.toml"];
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Cache {
+    version: u32,
+    entries: BTreeMap<PathBuf, Entry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Key {
+    cargo_toml_mtime: SystemTime,
+    rust_toolchain: PathBuf,
+    rust_toolchain_mtime: SystemTime,
+    revision: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Discovered {
+    pub name: String,
+    pub version: String,
+    pub lib_name: String,
+    pub toolchain: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Entry {
+    key: Key,
+    discovered: Discovered,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            entries: BTreeMap::new(),
+            dirty: false,
+        }
+    }
+}
+
+impl Cache {
+    /// Reads the cache. A missing, unparsable, or outdated cache is treated as empty.
+    pub fn read(metadata: &Metadata) -> Self {
+        read_to_string(cache_path(metadata))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Self>(&contents).ok())
+            .filter(|cache| cache.version == VERSION)
+            .unwrap_or_default()
+    }
+
+    /// Writes the cache, if it changed since it was read
+    pub fn write(&self, metadata: &Metadata) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = cache_path(metadata);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)
+                .with_context(|| format!("`create_dir_all` failed for `{}`", parent.display()))?;
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        write(&path, contents).with_context(|| format!("`write` failed for `{}`", path.display()))
+    }
+
+    pub fn get(&self, path: &Path, key: &Key) -> Option<&Discovered> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.key == *key)
+            .map(|entry| &entry.discovered)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, key: Key, discovered: Discovered) {
+        self.entries.insert(path, Entry { key, discovered });
+        self.dirty = true;
+    }
+}
+
+impl Key {
+    /// Returns `None` if `path` has no `Cargo.toml`, is governed by a `rustup override`, or is not
+    /// governed by a `rust-toolchain` file. In the latter two cases, the active toolchain depends
+    /// on things not reflected in the key (e.g., `rustup default`).
+    pub fn new(path: &Path, revision: Option<&str>) -> Result<Option<Self>> {
+        let Some(cargo_toml_mtime) = mtime(&path.join("Cargo.toml"))? else {
+            return Ok(None);
+        };
+
+        // smoelius: A directory override takes precedence over a `rust-toolchain` file.
+        if let Some(rustup_home) = rustup_home()
+            && has_rustup_override(&rustup_home, path)?
+        {
+            return Ok(None);
+        }
+
+        // smoelius: Like `rustup`, use the `rust-toolchain` file in the nearest ancestor that has
+        // one.
+        for ancestor in path.ancestors() {
+            for file_name in RUST_TOOLCHAIN_FILES {
+                let rust_toolchain = ancestor.join(file_name);
+                if let Some(rust_toolchain_mtime) = mtime(&rust_toolchain)? {
+                    return Ok(Some(Self {
+                        cargo_toml_mtime,
+                        rust_toolchain,
+                        rust_toolchain_mtime,
+                        revision: revision.map(ToOwned::to_owned),
+                    }));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Returns true if `rustup`'s settings contain an override for `path` or one of its ancestors
+fn has_rustup_override(rustup_home: &Path, path: &Path) -> Result<bool> {
+    let settings_toml = rustup_home.join("settings.toml");
+    if mtime(&settings_toml)?.is_none() {
+        return Ok(false);
+    }
+    let contents = read_to_string(&settings_toml)
+        .with_context(|| format!("`read_to_string` failed for `{}`", settings_toml.display()))?;
+    let settings = contents
+        .parse::<toml::Table>()
+        .with_context(|| format!("Could not parse `{}`", settings_toml.display()))?;
+    let Some(overrides) = settings.get("overrides").and_then(toml::Value::as_table) else {
+        return Ok(false);
+    };
+    let path = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    Ok(overrides.keys().any(|dir| path.starts_with(dir)))
+}
+
+fn mtime(path: &Path) -> Result<Option<SystemTime>> {
+    if !path
+        .try_exists()
+        .with_context(|| format!("Could not determine whether `{}` exists", path.display()))?
+    {
+        return Ok(None);
+    }
+    let modified = metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| format!("Could not get modification time of `{}`", path.display()))?;
+    Ok(Some(modified))
+}
+
+fn cache_path(metadata: &Metadata) -> PathBuf {
+    metadata
+        .target_directory
+        .join("dylint/discovery.json")
+        .into_std_path_buf()
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn key_changes_with_mtime() {
+        let tempdir = tempdir().unwrap();
+        let package = tempdir.path().join("package");
+        create_dir_all(&package).unwrap();
+
+        assert!(Key::new(&package, None).unwrap().is_none());
+
+        write(package.join("Cargo.toml"), "").unwrap();
+
+        // smoelius: No `rust-toolchain` file governs the package yet.
+        assert!(Key::new(&package, None).unwrap().is_none());
+
+        write(tempdir.path().join("rust-toolchain"), "").unwrap();
+
+        let key = Key::new(&package, None).unwrap().unwrap();
+        assert_eq!(tempdir.path().join("rust-toolchain"), key.rust_toolchain);
+
+        let mut cache = Cache::default();
+        cache.insert(
+            package.clone(),
+            key.clone(),
+            Discovered {
+                name: "package".to_owned(),
+                version: "0.1.0".to_owned(),
+                lib_name: "package".to_owned(),
+                toolchain: "nightly".to_owned(),
+            },
+        );
+        assert!(cache.get(&package, &key).is_some());
+
+        File::options()
+            .write(true)
+            .open(package.join("Cargo.toml"))
+            .unwrap()
+            .set_modified(key.cargo_toml_mtime + std::time::Duration::from_secs(1))
+            .unwrap();
+
+        let new_key = Key::new(&package, None).unwrap().unwrap();
+        assert!(cache.get(&package, &new_key).is_none());
+
+        let other_revision = Key::new(&package, Some("0123456")).unwrap().unwrap();
+        assert!(cache.get(&package, &other_revision).is_none());
+    }
+
+    #[test]
+    fn rustup_overrides_are_detected() {
+        let rustup_home = tempdir().unwrap();
+        let tempdir = tempdir().unwrap();
+        let package = dunce::canonicalize(tempdir.path()).unwrap().join("package");
+        create_dir_all(&package).unwrap();
+
+        assert!(!has_rustup_override(rustup_home.path(), &package).unwrap());
+
+        write(
+            rustup_home.path().join("settings.toml"),
+            format!(
+                "[overrides]\n{:?} = \"nightly\"\n",
+                tempdir.path().join("elsewhere")
+            ),
+        )
+        .unwrap();
+
+        assert!(!has_rustup_override(rustup_home.path(), &package).unwrap());
+
+        write(
+            rustup_home.path().join("settings.toml"),
+            format!(
+                "[overrides]\n{:?} = \"nightly\"\n",
+                dunce::canonicalize(tempdir.path()).unwrap()
+            ),
+        )
+        .unwrap();
+
+        assert!(has_rustup_override(rustup_home.path(), &package).unwrap());
+    }
+}
@@ -13,6 +13,9 @@
 //! by `cargo fetch`. On the other hand, if Cargo finds the dummy dependency in a completely new
 //! subdirectory, then that subdirectory must have been created by `cargo fetch`.
 //!
+//! Registry packages are simpler. The dummy project names the package directly. So `cargo
+//! metadata` reveals where `cargo fetch` unpacked it.
+//!
 //! [Marker]: https://github.com/rust-marker/marker
 
 use crate::opts;
@@ -74,10 +77,14 @@ impl PackageId {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub const fn version(&self) -> &Version {
+        &self.version
+    }
 }
 
 pub fn dependency_source_id_and_root(
-    _opts: &opts::Dylint,
+    opts: &opts::Dylint,
     metadata: &Metadata,
     _gctx: &GlobalContext,
     details: &TomlDetailedDependency,
@@ -88,7 +95,7 @@ pub fn dependency_source_id_and_root(
             "A dependency cannot have both git and path entries"
         );
         let source_id = git_source_id(url, details)?;
-        let root = git_dependency_root(url, details)?;
+        let root = git_dependency_root(opts, url, details)?;
         Ok((source_id, root))
     } else if let Some(path) = &details.path {
         let source_id = String::new();
@@ -98,8 +105,13 @@ pub fn dependency_source_id_and_root(
             .as_std_path()
             .to_path_buf();
         Ok((source_id, root))
+    } else if details.version.is_some() {
+        let name = details.package.as_ref().ok_or_else(|| {
+            anyhow!("A registry library must name its package with a `package` entry")
+        })?;
+        registry_source_id_and_root(opts, name, details)
     } else {
-        bail!("Only git and path entries are supported")
+        bail!("Only git, path, and registry entries are supported")
     }
 }
 
@@ -120,7 +132,11 @@ fn git_source_id(url: &str, details: &TomlDetailedDependency) -> Result<String>
     Ok(json)
 }
 
-fn git_dependency_root(url: &str, details: &TomlDetailedDependency) -> Result<PathBuf> {
+fn git_dependency_root(
+    opts: &opts::Dylint,
+    url: &str,
+    details: &TomlDetailedDependency,
+) -> Result<PathBuf> {
     let dependency = create_dummy_dependency()?;
     let filename = dependency
         .path()
@@ -134,6 +150,19 @@ fn git_dependency_root(url: &str, details: &TomlDetailedDependency) -> Result<Pa
     let ident = ident(url)?;
     let checkout_path = cargo_home.join("git/checkouts").join(ident);
 
+    // smoelius: Offline, `cargo fetch` cannot create the checkouts subdirectory. So fail early with
+    // an error that names the library.
+    if opts.offline()
+        && !checkout_path.try_exists().with_context(|| {
+            format!(
+                "Could not determine whether `{}` exists",
+                checkout_path.display()
+            )
+        })?
+    {
+        bail!("{}", not_cached_message(url));
+    }
+
     let mut errors = Vec::new();
     // smoelius: It should take at most two attempts to find the git dependency root. The first
     // attempt may fail because a new checkouts subdirectory had to be created. But the second
@@ -151,11 +180,11 @@ fn git_dependency_root(url: &str, details: &TomlDetailedDependency) -> Result<Pa
             BTreeMap::new()
         };
 
-        let output = cargo_fetch(package.path())?;
+        let output = cargo_fetch(opts, package.path())?;
 
         // smoelius: `cargo metadata` will fail if `cargo fetch` had to create a new checkouts
         // subdirectory.
-        let metadata = cargo_metadata(package.path()).ok();
+        let metadata = cargo_metadata(opts, package.path()).ok();
 
         match find_accessed_subdir(
             &dep_name,
@@ -192,9 +221,72 @@ fn git_dependency_root(url: &str, details: &TomlDetailedDependency) -> Result<Pa
     // smoelius: If we get here, it should be because `find_accessed_subdir` failed twice.
     debug_assert!(errors.len() >= 2);
 
+    // smoelius: Offline, the likeliest cause is that the requested commit was never fetched.
+    if opts.offline() {
+        bail!("{}: {errors:#?}", not_cached_message(url));
+    }
+
     Err(anyhow!("Could not find git dependency root: {errors:#?}"))
 }
 
+fn not_cached_message(url: &str) -> String {
+    format!(
+        "Git library `{url}` is not in Cargo's cache; run once without `--offline` (and with \
+         `CARGO_NET_OFFLINE` unset) to fetch it"
+    )
+}
+
+// smoelius: A registry package's root is its directory in Cargo's `registry/src` directory. Unlike
+// with git, there is no need to inject dummy dependencies: `cargo metadata` reports the root
+// directly.
+fn registry_source_id_and_root(
+    opts: &opts::Dylint,
+    name: &str,
+    details: &TomlDetailedDependency,
+) -> Result<(SourceId, PathBuf)> {
+    let package = create_dummy_package(name, details)?;
+
+    let output = cargo_fetch(opts, package.path())?;
+    ensure!(
+        output.status.success(),
+        "fetching registry library `{name}` failed\nstderr: {:?}",
+        String::from_utf8(output.stderr).unwrap_or_default()
+    );
+
+    let metadata = cargo_metadata(opts, package.path())?;
+
+    // smoelius: The dummy package has exactly one dependency, namely, the library package.
+    let dependency = metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| {
+            let root = resolve.root.as_ref()?;
+            let node = resolve.nodes.iter().find(|node| node.id == *root)?;
+            node.deps.first()
+        })
+        .and_then(|dep| {
+            metadata
+                .packages
+                .iter()
+                .find(|package| package.id == dep.pkg)
+        })
+        .ok_or_else(|| anyhow!("Could not find registry library `{name}` in dummy package"))?;
+
+    let source_id = dependency
+        .source
+        .as_ref()
+        .map(|source| source.repr.clone())
+        .unwrap_or_default();
+    let root = dependency
+        .manifest_path
+        .parent()
+        .ok_or_else(|| anyhow!("Could not get parent directory"))?
+        .as_std_path()
+        .to_path_buf();
+
+    Ok((source_id, root))
+}
+
 /// Creates a dummy dependency in a temporary directory, and returns the temporary directory if
 /// everything was successful.
 fn create_dummy_dependency() -> Result<TempDir> {
@@ -289,7 +381,7 @@ fn inject_dummy_dependencies(
     Ok(injected_dependencies)
 }
 
-fn cargo_fetch(path: &Path) -> Result<std::process::Output> {
+fn cargo_fetch(opts: &opts::Dylint, path: &Path) -> Result<std::process::Output> {
     // smoelius: `cargo fetch` could fail, e.g., if a new checkouts subdirectory had to be created.
     // But the command should still be executed.
     // smoelius: Since stdout and stderr are captured, there is no need to use `.quiet(true)`.
@@ -297,6 +389,7 @@ fn cargo_fetch(path: &Path) -> Result<std::process::Output> {
     dylint_internal::cargo::fetch("dummy package")
         .quiet(dylint_internal::cargo::Quiet::MESSAGE)
         .stable(true)
+        .offline(opts.offline())
         .build()
         .args([
             "--manifest-path",
@@ -307,12 +400,18 @@ fn cargo_fetch(path: &Path) -> Result<std::process::Output> {
         .logged_output(false)
 }
 
-fn cargo_metadata(path: &Path) -> Result<Metadata> {
-    MetadataCommand::new()
+fn cargo_metadata(opts: &opts::Dylint, path: &Path) -> Result<Metadata> {
+    // smoelius: Use `manifest_path` rather than `current_dir` so that, as with `cargo fetch`, Cargo
+    // reads the configuration of the current directory. That configuration could define the
+    // registry from which a library is downloaded.
+    let mut command = MetadataCommand::new();
+    command
         .cargo_path(dylint_internal::cargo::stable_cargo_path())
-        .current_dir(path)
-        .exec()
-        .map_err(Into::into)
+        .manifest_path(path.join("Cargo.toml"));
+    if opts.offline() {
+        command.other_options(vec!["--offline".to_owned()]);
+    }
+    command.exec().map_err(Into::into)
 }
 
 fn find_accessed_subdir<'a>(
@@ -0,0 +1,193 @@
+//! `dylint.lock` records what each git-sourced library entry resolved to: the commit, and for each
+//! package the entry matched, the package's name, version, and toolchain. Later runs fetch the
+//! recorded commit rather than whatever the entry's branch currently points to.
+
+use anyhow::{Context, Result, ensure};
+use cargo_metadata::Metadata;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+pub const DYLINT_LOCK: &str = "dylint.lock";
+
+const VERSION: u32 = 1;
+
+const HEADER: &str = "\
+# This file is automatically generated by Dylint.
+# It is not intended for manual editing. Use `cargo dylint update` to refresh it.
+";
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Lock {
+    version: u32,
+    #[serde(default, rename = "library", skip_serializing_if = "Vec::is_empty")]
+    libraries: Vec<LockedLibrary>,
+}
+
+/// A git-sourced library entry, as written in the workspace metadata or `dylint.toml`
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Source {
+    pub git: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LockedLibrary {
+    #[serde(flatten)]
+    pub source: Source,
+    pub commit: String,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct LockedPackage {
+    /// Path of the package's directory relative to the repository root, with `/` separators
+    pub path: String,
+    pub name: String,
+    pub version: String,
+    pub toolchain: String,
+}
+
+impl Default for Lock {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            libraries: Vec::new(),
+        }
+    }
+}
+
+impl Lock {
+    pub fn read(metadata: &Metadata) -> Result<Option<Self>> {
+        let path = lock_path(metadata);
+        if !path
+            .try_exists()
+            .with_context(|| format!("Could not determine whether `{}` exists", path.display()))?
+        {
+            return Ok(None);
+        }
+        let contents = read_to_string(&path)
+            .with_context(|| format!("`read_to_string` failed for `{}`", path.display()))?;
+        let lock: Self = toml::from_str(&contents)
+            .with_context(|| format!("Could not parse `{}`", path.display()))?;
+        ensure!(
+            lock.version == VERSION,
+            "`{}` has unsupported version {}; run `cargo dylint update` to regenerate it",
+            path.display(),
+            lock.version
+        );
+        Ok(Some(lock))
+    }
+
+    pub fn write(&self, metadata: &Metadata) -> Result<()> {
+        let path = lock_path(metadata);
+        let contents = toml::to_string(self)?;
+        write(&path, format!("{HEADER}\n{contents}"))
+            .with_context(|| format!("`write` failed for `{}`", path.display()))
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.libraries.is_empty()
+    }
+
+    pub fn get(&self, source: &Source) -> Option<&LockedLibrary> {
+        self.libraries
+            .iter()
+            .find(|library| library.source == *source)
+    }
+
+    pub fn insert(&mut self, library: LockedLibrary) {
+        self.libraries
+            .retain(|other| other.source != library.source);
+        self.libraries.push(library);
+        self.libraries
+            .sort_by(|left, right| left.source.cmp(&right.source));
+    }
+}
+
+impl LockedLibrary {
+    /// Returns an error if `packages` differ from the ones recorded
+    pub fn verify(&self, packages: &[LockedPackage]) -> Result<()> {
+        let mut packages = packages.to_vec();
+        packages.sort();
+        let mut locked = self.packages.clone();
+        locked.sort();
+        ensure!(
+            packages == locked,
+            "Library `{}` at commit {} no longer matches `{DYLINT_LOCK}`; run `cargo dylint \
+             update` to refresh it",
+            self.source.git,
+            self.commit
+        );
+        Ok(())
+    }
+}
+
+fn lock_path(metadata: &Metadata) -> PathBuf {
+    metadata
+        .workspace_root
+        .join(DYLINT_LOCK)
+        .into_std_path_buf()
+}
+
+/// Returns `path` relative to `root`, with `/` separators
+pub fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .iter()
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut lock = Lock::default();
+        lock.insert(LockedLibrary {
+            source: Source {
+                git: "https://github.com/trailofbits/dylint".to_owned(),
+                branch: None,
+                tag: None,
+                rev: None,
+                pattern: Some(vec!["examples/general".to_owned()]),
+                exclude: None,
+            },
+            commit: "0123456789abcdef0123456789abcdef01234567".to_owned(),
+            packages: vec![LockedPackage {
+                path: "examples/general".to_owned(),
+                name: "general".to_owned(),
+                version: "5.0.0".to_owned(),
+                toolchain: "nightly-2025-09-18-x86_64-unknown-linux-gnu".to_owned(),
+            }],
+        });
+
+        let contents = toml::to_string(&lock).unwrap();
+        assert_eq!(toml::from_str::<Lock>(&contents).unwrap(), lock);
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert_eq!(
+            relative_path(Path::new("/a/b"), Path::new("/a/b/c/d")),
+            "c/d"
+        );
+        assert_eq!(relative_path(Path::new("/a/b"), Path::new("/a/b")), "");
+    }
+}
@@ -5,14 +5,24 @@ use cargo_util_schemas::manifest::{StringOrVec, TomlDetailedDependency};
 use dylint_internal::{CommandExt, config, env, library_filename, rustup::SanitizeEnvironment};
 use glob::glob;
 use once_cell::sync::OnceCell;
+use semver::Version;
 use serde::{Deserialize, de::IntoDeserializer};
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 
 #[cfg(feature = "__cargo_cli")]
 #[path = "cargo_cli/mod.rs"]
 mod impl_;
 
-use impl_::{GlobalContext, PackageId, SourceId, dependency_source_id_and_root};
+use impl_::{GlobalContext, PackageId, dependency_source_id_and_root};
+
+mod cache;
+use cache::{Cache, Discovered, Key};
+
+mod lock;
+use lock::{Lock, LockedLibrary, LockedPackage, Source, relative_path};
 
 type Object = serde_json::Map<String, serde_json::Value>;
 
@@ -78,10 +88,46 @@ impl Package {
 #[derive(Debug, Deserialize)]
 struct Library {
     pattern: Option<StringOrVec>,
+    exclude: Option<StringOrVec>,
     #[serde(flatten)]
     details: TomlDetailedDependency,
 }
 
+/// A value in `dylint.toml`'s `lints` table. As in Cargo's `[lints]` table, a value is either a
+/// level or a table with `level` and `priority` keys.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LintConfig {
+    Level(LintLevel),
+    Detailed {
+        level: LintLevel,
+        #[serde(default)]
+        priority: i8,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+    ForceWarn,
+}
+
+impl LintLevel {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Deny => "deny",
+            Self::Forbid => "forbid",
+            Self::ForceWarn => "force-warn",
+        }
+    }
+}
+
 pub fn from_opts(opts: &opts::Dylint) -> Result<Vec<Package>> {
     let lib_sel = opts.library_selection();
 
@@ -121,9 +167,15 @@ pub fn from_opts(opts: &opts::Dylint) -> Result<Vec<Package>> {
             .pattern
             .as_ref()
             .map(|pattern| StringOrVec(vec![pattern.clone()])),
+        exclude: if lib_sel.excludes.is_empty() {
+            None
+        } else {
+            Some(StringOrVec(lib_sel.excludes.clone()))
+        },
     };
 
-    library_packages(opts, metadata, &[library])
+    // smoelius: Libraries named on the command line are not recorded in `dylint.lock`.
+    library_packages(opts, metadata, &[library], false)
 }
 
 pub fn from_workspace_metadata(opts: &opts::Dylint) -> Result<Vec<Package>> {
@@ -149,6 +201,34 @@ pub fn from_dylint_toml(opts: &opts::Dylint) -> Result<Vec<Package>> {
     }
 }
 
+/// Returns the lint names and levels in the `lints` table of the workspace's `dylint.toml` file.
+/// The entries are sorted by priority so that later entries take precedence over earlier ones.
+pub fn dylint_toml_lints(opts: &opts::Dylint) -> Result<Vec<(String, &'static str)>> {
+    let Some(metadata) = cargo_metadata(opts)? else {
+        return Ok(Vec::new());
+    };
+    let _ = config::try_init_with_metadata(metadata)?;
+    let Some(value) = config::get().and_then(|table| table.get("lints")) else {
+        return Ok(Vec::new());
+    };
+    let lints = BTreeMap::<String, LintConfig>::deserialize(value.clone().into_deserializer())
+        .with_context(|| "Could not parse `lints` table in `dylint.toml`")?;
+    let mut lints = lints
+        .into_iter()
+        .map(|(name, lint_config)| match lint_config {
+            LintConfig::Level(level) => (0, name, level),
+            LintConfig::Detailed { level, priority } => (priority, name, level),
+        })
+        .collect::<Vec<_>>();
+    // smoelius: Like Cargo, order by priority, then by name. `lints` is already ordered by name and
+    // `sort_by_key` is stable.
+    lints.sort_by_key(|&(priority, _, _)| priority);
+    Ok(lints
+        .into_iter()
+        .map(|(_, name, level)| (name, level.as_str()))
+        .collect())
+}
+
 static CARGO_METADATA: OnceCell<Option<Metadata>> = OnceCell::new();
 
 fn cargo_metadata(opts: &opts::Dylint) -> Result<Option<&'static Metadata>> {
@@ -166,6 +246,12 @@ fn cargo_metadata(opts: &opts::Dylint) -> Result<Option<&'static Metadata>> {
                 command.manifest_path(path);
             }
 
+            // smoelius: Metadata commands that use `--no-deps` do not resolve dependencies. So only
+            // this command and the ones in `cargo_cli` need `--offline`.
+            if opts.offline() {
+                command.other_options(vec!["--offline".to_owned()]);
+            }
+
             match command.exec() {
                 Ok(metadata) => Ok(Some(metadata)),
                 Err(err) => {
@@ -218,7 +304,7 @@ fn library_packages_from_dylint_metadata(
         .map(|(key, value)| {
             if key == "libraries" {
                 let libraries = serde_json::from_value::<Vec<Library>>(value.clone())?;
-                library_packages(opts, metadata, &libraries)
+                library_packages(opts, metadata, &libraries, true)
             } else {
                 bail!("Unknown key `{key}`")
             }
@@ -251,7 +337,7 @@ fn library_packages_from_dylint_toml(
         .map(|(key, value)| {
             if key == "libraries" {
                 let libraries = Vec::<Library>::deserialize(value.clone().into_deserializer())?;
-                library_packages(opts, metadata, &libraries)
+                library_packages(opts, metadata, &libraries, true)
             } else {
                 bail!("Unknown key `{key}`")
             }
@@ -264,15 +350,56 @@ fn library_packages(
     opts: &opts::Dylint,
     metadata: &'static Metadata,
     libraries: &[Library],
+    use_lock: bool,
 ) -> Result<Vec<Package>> {
     let gctx = GlobalContext::default()?;
 
+    let existing_lock = if use_lock {
+        Lock::read(metadata)?
+    } else {
+        None
+    };
+
+    // smoelius: `cargo dylint update` ignores the existing lock so that every git entry is
+    // resolved anew.
+    let honored_lock = if matches!(opts.operation, opts::Operation::Update(_)) {
+        None
+    } else {
+        existing_lock.as_ref()
+    };
+
+    let mut new_lock = Lock::default();
+
+    let mut cache = Cache::read(metadata);
+
     let packages = libraries
         .iter()
-        .map(|library| library_package(opts, metadata, &gctx, library))
+        .map(|library| {
+            library_package(
+                opts,
+                metadata,
+                &gctx,
+                library,
+                honored_lock,
+                &mut new_lock,
+                &mut cache,
+            )
+        })
         .collect::<Result<Vec<_>>>()
         .with_context(|| "Could not build metadata entries")?;
 
+    // smoelius: Failing to write the cache should not prevent Dylint from running.
+    if let Err(error) = cache.write(metadata) {
+        warn(opts, &format!("Could not write discovery cache: {error}"));
+    }
+
+    if use_lock
+        && (existing_lock.is_some() || !new_lock.is_empty())
+        && existing_lock.as_ref() != Some(&new_lock)
+    {
+        new_lock.write(metadata)?;
+    }
+
     Ok(packages.into_iter().flatten().collect())
 }
 
@@ -281,9 +408,23 @@ fn library_package(
     metadata: &'static Metadata,
     gctx: &GlobalContext,
     library: &Library,
+    honored_lock: Option<&Lock>,
+    new_lock: &mut Lock,
+    cache: &mut Cache,
 ) -> Result<Vec<Package>> {
     let details = toml_detailed_dependency(library)?;
 
+    let source = lock_source(library, details);
+
+    let locked = source
+        .as_ref()
+        .and_then(|source| honored_lock.and_then(|lock| lock.get(source)));
+
+    // smoelius: If the entry is locked, fetch the locked commit rather than whatever the entry's
+    // branch, etc. currently refers to.
+    let pinned = locked.map(|locked| pin(details, &locked.commit));
+    let details = pinned.as_ref().unwrap_or(details);
+
     // smoelius: The dependency root cannot be canonicalized here. It could contain a `glob` pattern
     // (e.g., `*`), because Dylint allows `path` entries to contain `glob` patterns.
     let (source_id, dependency_root) =
@@ -343,6 +484,20 @@ fn library_package(
         }
     }
 
+    // smoelius: Remove excluded paths before computing package metadata and active toolchains,
+    // both of which are slow, and either of which could fail for a broken library. Excluding a
+    // directory excludes every path within it.
+    if let Some(StringOrVec(excludes)) = &library.exclude {
+        let mut excluded = BTreeSet::new();
+        for exclude in excludes {
+            for result in glob(&dependency_root.join(exclude).to_string_lossy())? {
+                let path = result?;
+                excluded.insert(cargo_util::paths::normalize_path(&path));
+            }
+        }
+        paths.retain(|path| !excluded.iter().any(|excluded| path.starts_with(excluded)));
+    }
+
     // smoelius: Collecting the package ids before building reveals missing/unparsable `Cargo.toml`
     // files sooner.
 
@@ -352,39 +507,121 @@ fn library_package(
     // workspace are intended to be built with the same version of the compiler"
     // (https://github.com/rust-lang/rustup/issues/1399#issuecomment-383376082).
 
+    let commit = source
+        .as_ref()
+        .map(|_| dylint_internal::head_commit(&dependency_root))
+        .transpose()?;
+
     // smoelius: Experiments suggest that a considerable amount of Dylint's start up time is spent
     // in the following loop, and a considerable (though not necessarily dominant) fraction of that
-    // is spent in `active_toolchain`.
+    // is spent in `active_toolchain`. Hence, the loop consults the discovery cache first.
     let mut packages = Vec::new();
     for path in paths {
         if !path.is_dir() {
             continue;
         }
-        // smoelius: Ignore subdirectories that do not contain packages.
-        let package = match package_with_root(&path) {
-            Ok(package) => package,
-            Err(error) => {
-                warn(opts, &error.to_string());
-                continue;
-            }
+        let key = Key::new(&path, commit.as_deref())?;
+        let Some(discovered) = discover(opts, &path, key, cache)? else {
+            continue;
         };
+        let version = Version::parse(&discovered.version)
+            .with_context(|| format!("Could not parse version `{}`", discovered.version))?;
         // smoelius: When `__cargo_cli` is enabled, `source_id`'s type is `String`.
         #[allow(clippy::clone_on_copy)]
-        let package_id = package_id(&package, source_id.clone());
-        let lib_name = package_library_name(&package)?;
-        let toolchain = dylint_internal::rustup::active_toolchain(&path)?;
+        let package_id = PackageId::new(discovered.name, version, source_id.clone());
         packages.push(Package {
             metadata,
             root: path,
             id: package_id,
-            lib_name,
-            toolchain,
+            lib_name: discovered.lib_name,
+            toolchain: discovered.toolchain,
+        });
+    }
+
+    if let Some(source) = source
+        && let Some(commit) = commit
+    {
+        let locked_packages = locked_packages(&dependency_root, &packages);
+        if let Some(locked) = locked {
+            locked.verify(&locked_packages)?;
+        }
+        new_lock.insert(LockedLibrary {
+            source,
+            commit,
+            packages: locked_packages,
         });
     }
 
     Ok(packages)
 }
 
+fn discover(
+    opts: &opts::Dylint,
+    path: &Path,
+    key: Option<Key>,
+    cache: &mut Cache,
+) -> Result<Option<Discovered>> {
+    if let Some(discovered) = key.as_ref().and_then(|key| cache.get(path, key)) {
+        return Ok(Some(discovered.clone()));
+    }
+    // smoelius: Ignore subdirectories that do not contain packages.
+    let package = match package_with_root(path) {
+        Ok(package) => package,
+        Err(error) => {
+            warn(opts, &error.to_string());
+            return Ok(None);
+        }
+    };
+    let discovered = Discovered {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        lib_name: package_library_name(&package)?,
+        toolchain: dylint_internal::rustup::active_toolchain(path)?,
+    };
+    if let Some(key) = key {
+        cache.insert(path.to_path_buf(), key, discovered.clone());
+    }
+    Ok(Some(discovered))
+}
+
+fn lock_source(library: &Library, details: &TomlDetailedDependency) -> Option<Source> {
+    details.git.as_ref().map(|git| Source {
+        git: git.clone(),
+        branch: details.branch.clone(),
+        tag: details.tag.clone(),
+        rev: details.rev.clone(),
+        pattern: library
+            .pattern
+            .as_ref()
+            .map(|StringOrVec(patterns)| patterns.clone()),
+        exclude: library
+            .exclude
+            .as_ref()
+            .map(|StringOrVec(excludes)| excludes.clone()),
+    })
+}
+
+fn pin(details: &TomlDetailedDependency, commit: &str) -> TomlDetailedDependency {
+    let mut details = details.clone();
+    details.branch = None;
+    details.tag = None;
+    details.rev = Some(commit.to_owned());
+    details
+}
+
+fn locked_packages(dependency_root: &Path, packages: &[Package]) -> Vec<LockedPackage> {
+    let dependency_root = cargo_util::paths::normalize_path(dependency_root);
+    packages
+        .iter()
+        .map(|package| LockedPackage {
+            path: relative_path(&dependency_root, &package.root),
+            name: package.id.name().to_owned(),
+            version: package.id.version().to_string(),
+            toolchain: package.toolchain.clone(),
+        })
+        .collect()
+}
+
 fn toml_detailed_dependency(library: &Library) -> Result<&TomlDetailedDependency> {
     let mut unused_keys = library
         .details
@@ -409,8 +646,8 @@ fn toml_detailed_dependency(library: &Library) -> Result<&TomlDetailedDependency
 }
 
 fn package_with_root(package_root: &Path) -> Result<MetadataPackage> {
-    // smoelius: For the long term, we should investigate having a "cache" that maps paths to
-    // `cargo_metadata::Metadata`.
+    // smoelius: `library_package` calls this function only when the discovery cache (see
+    // `cache.rs`) has no entry for `package_root`.
     // smoelius: Both `cargo_path` and `sanitize_environment` must be called. Without the call to
     // `cargo_path`, `cargo_metadata` will use `$CARGO`. Without the call to `sanitize_environment`,
     // `CARGO`, etc. will be set and the `rustup` proxy will invoke the wrong `cargo`.
@@ -424,15 +661,6 @@ fn package_with_root(package_root: &Path) -> Result<MetadataPackage> {
     dylint_internal::cargo::package_with_root(&metadata, package_root)
 }
 
-fn package_id(package: &MetadataPackage, source_id: SourceId) -> PackageId {
-    PackageId::new(
-        #[allow(clippy::useless_conversion)]
-        package.name.to_string().into(),
-        package.version.clone(),
-        source_id,
-    )
-}
-
 pub fn package_library_name(package: &MetadataPackage) -> Result<String> {
     package
         .targets
@@ -462,6 +690,7 @@ pub fn build_library(opts: &opts::Dylint, package: &Package) -> Result<PathBuf>
         // to be rebuilt.
         dylint_internal::cargo::build(&format!("workspace metadata entry `{}`", package.id.name()))
             .quiet(opts.quiet)
+            .offline(opts.offline())
             .build()
             .sanitize_environment()
             .env_remove(env::RUSTFLAGS)
@@ -0,0 +1,147 @@
+use crate::{LintLevels, ToolchainMap, library_lints, opts};
+use anyhow::{Context, Result, ensure};
+use glob::Pattern;
+use std::collections::BTreeMap;
+
+/// Narrows `resolved` to the libraries that define a lint matching one of the `--lint` patterns.
+/// Every other lint those libraries register is allowed.
+///
+/// A lint defined by more than one library (e.g., by both a composite library and the corresponding
+/// single-lint library) is attributed to just one of them, namely, the one defining the fewest
+/// lints.
+pub fn select(opts: &opts::Dylint, resolved: ToolchainMap) -> Result<(ToolchainMap, LintLevels)> {
+    let lib_sel = opts.library_selection();
+
+    let patterns = lib_sel
+        .lints
+        .iter()
+        .map(|name| {
+            Pattern::new(&normalize(name))
+                .with_context(|| format!("Invalid pattern `--lint {name}`"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut libraries = Vec::new();
+    for (toolchain, paths) in resolved {
+        for path in paths {
+            let lints = library_lints(opts, &toolchain, &path)?;
+            libraries.push((toolchain.clone(), path, lints));
+        }
+    }
+
+    let mut matched = vec![false; patterns.len()];
+
+    // smoelius: Map each selected lint to the index of the library it is attributed to.
+    let mut owners = BTreeMap::<String, usize>::new();
+    for (index, (_, _, lints)) in libraries.iter().enumerate() {
+        for lint in lints {
+            let mut is_selected = false;
+            for (pattern, matched) in patterns.iter().zip(&mut matched) {
+                if pattern.matches(&lint.name) {
+                    *matched = true;
+                    is_selected = true;
+                }
+            }
+            if !is_selected {
+                continue;
+            }
+            let owner = owners.entry(lint.name.clone()).or_insert(index);
+            if lints.len() < libraries[*owner].2.len() {
+                *owner = index;
+            }
+        }
+    }
+
+    for (name, matched) in lib_sel.lints.iter().zip(matched) {
+        ensure!(
+            matched,
+            "No loaded library defines a lint matching `--lint {name}`"
+        );
+    }
+
+    let owner_toolchains = owners
+        .iter()
+        .map(|(name, &owner)| (name.as_str(), libraries[owner].0.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut selected = ToolchainMap::new();
+    let mut lint_levels = LintLevels::new();
+
+    for (index, (toolchain, path, lints)) in libraries.iter().enumerate() {
+        let mut defines_selected = false;
+        let mut levels = Vec::new();
+
+        for lint in lints {
+            match owners.get(&lint.name) {
+                Some(&owner) if owner == index => {
+                    defines_selected = true;
+                    // smoelius: Otherwise, selecting a lint that is allowed by default would do
+                    // nothing.
+                    if lint.level == "allow" {
+                        levels.push((lint.name.clone(), "warn"));
+                    }
+                }
+                // smoelius: Lint levels apply to every library loaded with a toolchain. So a lint
+                // attributed to another library with the same toolchain must not be allowed.
+                Some(_) if owner_toolchains.get(lint.name.as_str()) == Some(toolchain) => {}
+                _ => levels.push((lint.name.clone(), "allow")),
+            }
+        }
+
+        if defines_selected {
+            selected
+                .entry(toolchain.clone())
+                .or_default()
+                .insert(path.clone());
+            lint_levels
+                .entry(toolchain.clone())
+                .or_default()
+                .extend(levels);
+        }
+    }
+
+    Ok((selected, lint_levels))
+}
+
+/// Like rustc, accept lint names with hyphens in place of underscores. Hyphens within character
+/// classes (e.g., `[a-z]`) denote ranges, and so are left alone.
+fn normalize(pattern: &str) -> String {
+    let mut normalized = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '-' => normalized.push('_'),
+            '[' => {
+                normalized.push(c);
+                // smoelius: A `]` immediately following `[` or `[!` is part of the class.
+                if let Some(c) = chars.next_if_eq(&'!') {
+                    normalized.push(c);
+                }
+                if let Some(c) = chars.next_if_eq(&']') {
+                    normalized.push(c);
+                }
+                for c in chars.by_ref() {
+                    normalized.push(c);
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            _ => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalize_leaves_character_classes_alone() {
+        assert_eq!("env_literal", normalize("env-literal"));
+        assert_eq!("[a-z]*_literal", normalize("[a-z]*-literal"));
+        assert_eq!("[!]-]_x", normalize("[!]-]-x"));
+        assert_eq!("[]-]_x", normalize("[]-]-x"));
+    }
+}
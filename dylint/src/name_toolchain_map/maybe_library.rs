@@ -14,6 +14,11 @@ impl MaybeLibrary {
     pub fn build(&self, opts: &crate::opts::Dylint) -> Result<PathBuf> {
         self.inner.build(opts)
     }
+
+    /// Returns the directory in which the library is built, if Dylint builds it
+    pub fn target_directory(&self) -> Option<PathBuf> {
+        self.inner.target_directory()
+    }
 }
 
 impl From<PathBuf> for MaybeLibrary {
@@ -51,6 +56,19 @@ impl Inner {
         }
     }
 
+    #[cfg_attr(
+        not(__library_packages),
+        allow(clippy::missing_const_for_fn, clippy::unnecessary_wraps)
+    )]
+    fn target_directory(&self) -> Option<PathBuf> {
+        match self {
+            Self::Path(_) => None,
+
+            #[cfg(__library_packages)]
+            Self::Package(package) => Some(package.target_directory()),
+        }
+    }
+
     #[cfg_attr(
         not(__library_packages),
         allow(unused_variables, clippy::unnecessary_wraps)
@@ -16,7 +16,7 @@
 //!
 //! [struct update syntax]: https://doc.rust-lang.org/book/ch05-01-defining-structs.html#creating-instances-from-other-instances-with-struct-update-syntax
 
-#[cfg(feature = "package_options")]
+use dylint_internal::env;
 use std::sync::LazyLock;
 
 #[allow(clippy::struct_excessive_bools)]
@@ -31,24 +31,31 @@ pub struct Dylint {
     pub operation: Operation,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug, Default)]
 pub struct LibrarySelection {
     pub all: bool,
 
     pub branch: Option<String>,
 
+    pub excludes: Vec<String>,
+
     pub git: Option<String>,
 
     pub lib_paths: Vec<String>,
 
     pub libs: Vec<String>,
 
+    pub lints: Vec<String>,
+
     pub manifest_path: Option<String>,
 
     pub no_build: bool,
 
     pub no_metadata: bool,
 
+    pub offline: bool,
+
     pub paths: Vec<String>,
 
     pub pattern: Option<String>,
@@ -62,9 +69,13 @@ pub struct LibrarySelection {
 #[non_exhaustive]
 pub enum Operation {
     Check(Check),
+    Clean(Clean),
+    Config(Config),
+    Driver(Driver),
     List(List),
     #[cfg(feature = "package_options")]
     New(New),
+    Update(Update),
     #[cfg(feature = "package_options")]
     Upgrade(Upgrade),
 }
@@ -74,22 +85,108 @@ pub enum Operation {
 pub struct Check {
     pub lib_sel: LibrarySelection,
 
+    pub baseline: Option<String>,
+
+    pub diff_base: Option<String>,
+
     pub fix: bool,
 
+    pub jobs: Option<usize>,
+
     pub keep_going: bool,
 
     pub no_deps: bool,
 
+    pub output_file: Option<String>,
+
+    pub output_format: OutputFormat,
+
     pub packages: Vec<String>,
 
     pub workspace: bool,
 
+    pub write_baseline: Option<String>,
+
     pub args: Vec<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Clean {
+    pub lib_sel: LibrarySelection,
+
+    pub dry_run: bool,
+
+    pub toolchain: Option<String>,
+
+    pub unreferenced: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub lib_sel: LibrarySelection,
+
+    pub schema: bool,
+
+    pub validate: bool,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Driver {
+    Build(DriverBuild),
+    Clean,
+    #[cfg(feature = "package_options")]
+    Export(DriverExport),
+    #[cfg(feature = "package_options")]
+    Import(DriverImport),
+    List,
+    Prune(Box<DriverPrune>),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DriverBuild {
+    pub toolchain: String,
+}
+
+#[cfg(feature = "package_options")]
+#[derive(Clone, Debug, Default)]
+pub struct DriverExport {
+    pub toolchain: String,
+
+    pub archive: String,
+}
+
+#[cfg(feature = "package_options")]
+#[derive(Clone, Debug, Default)]
+pub struct DriverImport {
+    pub archive: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DriverPrune {
+    pub lib_sel: LibrarySelection,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Combined,
+    Sarif,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct List {
     pub lib_sel: LibrarySelection,
+
+    pub format: ListFormat,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ListFormat {
+    #[default]
+    Text,
+    Json,
 }
 
 #[cfg(feature = "package_options")]
@@ -100,6 +197,11 @@ pub struct New {
     pub path: String,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Update {
+    pub lib_sel: LibrarySelection,
+}
+
 #[cfg(feature = "package_options")]
 #[derive(Clone, Debug, Default)]
 pub struct Upgrade {
@@ -130,6 +232,24 @@ impl Dylint {
     pub(crate) fn git_or_path(&self) -> bool {
         self.library_selection().git_or_path()
     }
+
+    /// Returns true if `--offline` was passed or `CARGO_NET_OFFLINE` is set to `true`, in which
+    /// case every `cargo` command Dylint runs is passed `--offline`.
+    pub(crate) fn offline(&self) -> bool {
+        (self.has_library_selection() && self.library_selection().offline)
+            || std::env::var(env::CARGO_NET_OFFLINE).is_ok_and(|value| value == "true")
+    }
+}
+
+impl Check {
+    /// Returns true if diagnostics must be collected from `cargo check`'s JSON output, rather than
+    /// simply forwarded to the user.
+    pub(crate) const fn collects_diagnostics(&self) -> bool {
+        !matches!(self.output_format, OutputFormat::Human)
+            || self.baseline.is_some()
+            || self.diff_base.is_some()
+            || self.write_baseline.is_some()
+    }
 }
 
 impl LibrarySelection {
@@ -138,13 +258,18 @@ impl LibrarySelection {
     }
 }
 
-#[cfg(feature = "package_options")]
 static LIBRARY_SELECTION: LazyLock<LibrarySelection> = LazyLock::new(LibrarySelection::default);
 
 impl Operation {
     const fn has_library_selection(&self) -> bool {
         match self {
-            Self::Check(_) | Self::List(_) => true,
+            Self::Check(_)
+            | Self::Clean(_)
+            | Self::Config(_)
+            | Self::Driver(Driver::Prune(_))
+            | Self::List(_)
+            | Self::Update(_) => true,
+            Self::Driver(_) => false,
             #[cfg(feature = "package_options")]
             Self::New(_) | Self::Upgrade(_) => false,
         }
@@ -154,40 +279,46 @@ impl Operation {
     fn library_selection(&self) -> &LibrarySelection {
         match self {
             Self::Check(check) => &check.lib_sel,
+            Self::Clean(clean) => &clean.lib_sel,
+            Self::Config(config) => &config.lib_sel,
+            Self::Driver(Driver::Prune(prune)) => &prune.lib_sel,
             Self::List(list) => &list.lib_sel,
-            #[cfg(feature = "package_options")]
-            Self::New(_) | Self::Upgrade(_) => {
-                if cfg!(debug_assertions) {
-                    eprintln!(
-                        "[{}:{}] {}",
-                        file!(),
-                        line!(),
-                        "`library_selection` called on an `Operation` with no `LibrarySelection` \
-                         field"
-                    );
-                    eprintln!("{}", std::backtrace::Backtrace::force_capture());
-                }
-                &LIBRARY_SELECTION
-            }
+            Self::Update(update) => &update.lib_sel,
+            _ => no_library_selection(),
         }
     }
 
-    #[allow(clippy::panic)]
     fn library_selection_mut(&mut self) -> &mut LibrarySelection {
         match self {
             Self::Check(check) => &mut check.lib_sel,
+            Self::Clean(clean) => &mut clean.lib_sel,
+            Self::Config(config) => &mut config.lib_sel,
+            Self::Driver(Driver::Prune(prune)) => &mut prune.lib_sel,
             Self::List(list) => &mut list.lib_sel,
-            #[cfg(feature = "package_options")]
-            Self::New(_) | Self::Upgrade(_) => {
-                panic!(
-                    "`library_selection_mut` called on an `Operation` with no `LibrarySelection` \
-                     field"
-                )
-            }
+            Self::Update(update) => &mut update.lib_sel,
+            _ => no_library_selection_mut(),
         }
     }
 }
 
+fn no_library_selection() -> &'static LibrarySelection {
+    if cfg!(debug_assertions) {
+        eprintln!(
+            "[{}:{}] {}",
+            file!(),
+            line!(),
+            "`library_selection` called on an `Operation` with no `LibrarySelection` field"
+        );
+        eprintln!("{}", std::backtrace::Backtrace::force_capture());
+    }
+    &LIBRARY_SELECTION
+}
+
+#[allow(clippy::panic)]
+fn no_library_selection_mut() -> &'static mut LibrarySelection {
+    panic!("`library_selection_mut` called on an `Operation` with no `LibrarySelection` field")
+}
+
 impl Default for Operation {
     fn default() -> Self {
         Self::Check(Check::default())
@@ -93,6 +93,8 @@ struct Config {
     ignore: Vec<String>,
 }
 
+dylint_linting::config_schema!(Config);
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum Macro {
     Builtin(Symbol),
@@ -127,6 +127,8 @@ struct Config {
     presence_penalty: Option<f32>,
 }
 
+dylint_linting::config_schema!(Config);
+
 struct MissingDocCommentOpenai {
     config: Config,
 }
@@ -54,6 +54,7 @@ dylint_linting::impl_late_lint! {
     pub ABS_HOME_PATH,
     Warn,
     "string literals that are absolute paths into the user's home directory",
+    group = "dylint_general",
     AbsHomePath::default()
 }
 
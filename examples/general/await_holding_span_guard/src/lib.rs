@@ -84,7 +84,8 @@ dylint_linting::declare_late_lint! {
     /// [#6353]: https://github.com/rust-lang/rust-clippy/issues/6353
     pub AWAIT_HOLDING_SPAN_GUARD,
     Warn,
-    "Inside an async function, holding a Span guard while calling await"
+    "Inside an async function, holding a Span guard while calling await",
+    group = "dylint_general"
 }
 
 const TRACING_SPAN_ENTER_GUARD: [&str; 3] = ["tracing", "span", "Entered"];
@@ -44,6 +44,7 @@ dylint_linting::impl_late_lint! {
     pub BASIC_DEAD_STORE,
     Warn,
     "An array element is assigned twice without a use or read in between",
+    group = "dylint_general",
     BasicDeadStore::default()
 }
 
@@ -35,7 +35,8 @@ dylint_linting::declare_early_lint! {
     /// ```
     pub CRATE_WIDE_ALLOW,
     Warn,
-    "use of `#![allow(...)]` at the crate level"
+    "use of `#![allow(...)]` at the crate level",
+    group = "dylint_general"
 }
 
 impl EarlyLintPass for CrateWideAllow {
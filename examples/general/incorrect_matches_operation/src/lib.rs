@@ -59,7 +59,8 @@ dylint_linting::declare_pre_expansion_lint! {
     /// ```
     pub INCORRECT_MATCHES_OPERATION,
     Warn,
-    "inefficient `matches!` macro use"
+    "inefficient `matches!` macro use",
+    group = "dylint_general"
 }
 
 fn is_matches_macro(expr: &Expr) -> Option<&MacCall> {
@@ -98,6 +98,7 @@ dylint_linting::impl_late_lint! {
     pub NON_LOCAL_EFFECT_BEFORE_ERROR_RETURN,
     Warn,
     "non-local effects before return of an error",
+    group = "dylint_general",
     NonLocalEffectBeforeErrorReturn::new()
 }
 
@@ -107,6 +108,8 @@ struct Config {
     work_limit: Option<u64>,
 }
 
+dylint_linting::config_schema!(Config);
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -478,11 +481,43 @@ fn ui() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui");
 }
 
+// smoelius: `ui_public_only/main.dylint.toml` sets `public_only = false`. A per-file configuration
+// applies to every run of its file. So checking the same source under both configurations requires
+// two copies of it. `ui_main_rs_equal` keeps the copies in sync.
 #[test]
 fn ui_public_only() {
-    dylint_testing::ui::Test::example(env!("CARGO_PKG_NAME"), "ui_public_only")
-        .dylint_toml("non_local_effect_before_error_return.public_only = false")
+    dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui_public_only");
+}
+
+// smoelius: `ui_per_file/all_functions.rs` sets `public_only = false` with a header, while
+// `ui_per_file/public_only.rs` uses the default configuration. So the two files are run separately.
+#[test]
+fn ui_per_file() {
+    dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), "ui_per_file").run();
+}
+
+#[test]
+fn ui_per_file_bless() {
+    let tempdir = tempfile::tempdir().unwrap();
+    for file_name in ["all_functions.rs", "public_only.rs"] {
+        std::fs::copy(
+            std::path::Path::new("ui_per_file").join(file_name),
+            tempdir.path().join(file_name),
+        )
+        .unwrap();
+    }
+
+    dylint_testing::ui::Test::src_base(env!("CARGO_PKG_NAME"), tempdir.path())
+        .bless()
         .run();
+
+    // smoelius: `all_functions.rs` is run from a temporary directory of its own. Its blessed output
+    // must be copied back.
+    assert_eq!(
+        std::fs::read_to_string("ui_per_file/all_functions.stderr").unwrap(),
+        std::fs::read_to_string(tempdir.path().join("all_functions.stderr")).unwrap()
+    );
+    assert!(!tempdir.path().join("public_only.stderr").exists());
 }
 
 #[test]
@@ -0,0 +1,12 @@
+//@ dylint-toml: non_local_effect_before_error_return.public_only = false
+
+#![expect(dead_code)]
+
+use std::env::VarError;
+
+fn main() {}
+
+fn deref_assign_before_err_return(flag: &mut bool) -> Result<(), VarError> {
+    *flag = true;
+    Err(VarError::NotPresent)
+}
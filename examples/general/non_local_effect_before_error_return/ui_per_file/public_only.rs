@@ -0,0 +1,10 @@
+#![expect(dead_code)]
+
+use std::env::VarError;
+
+fn main() {}
+
+fn deref_assign_before_err_return(flag: &mut bool) -> Result<(), VarError> {
+    *flag = true;
+    Err(VarError::NotPresent)
+}
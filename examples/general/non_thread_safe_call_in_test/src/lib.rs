@@ -17,6 +17,7 @@ mod late;
 pub fn register_lints(_sess: &rustc_session::Session, lint_store: &mut rustc_lint::LintStore) {
     lint_store.register_lints(&[late::NON_THREAD_SAFE_CALL_IN_TEST]);
     lint_store.register_late_pass(|_| Box::<late::NonThreadSafeCallInTest>::default());
+    dylint_linting::add_to_group("dylint_general", late::NON_THREAD_SAFE_CALL_IN_TEST);
 }
 
 #[test]
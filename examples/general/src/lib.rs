@@ -3,6 +3,9 @@
 
 dylint_linting::dylint_library!();
 
+// smoelius: Please keep the following arguments sorted by crate name.
+dylint_linting::config_schemas!(non_local_effect_before_error_return);
+
 extern crate rustc_lint;
 extern crate rustc_session;
 
@@ -91,6 +91,7 @@ dylint_linting::impl_late_lint! {
     pub WRONG_SERIALIZE_STRUCT_ARG,
     Warn,
     "calls to serialization methods with incorrect `len` arguments",
+    group = "dylint_general",
     WrongSerializeStructArg::default()
 }
 
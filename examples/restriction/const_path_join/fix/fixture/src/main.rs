@@ -0,0 +1,6 @@
+mod paths;
+
+fn main() {
+    let _ = paths::target_dir();
+    let _ = std::path::Path::new("..").join("target");
+}
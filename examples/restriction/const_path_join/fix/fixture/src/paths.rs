@@ -0,0 +1,3 @@
+pub fn target_dir() -> std::path::PathBuf {
+    std::path::PathBuf::from("..").join("target")
+}
@@ -178,3 +178,8 @@ fn is_path_buf_from(
 fn ui() {
     dylint_testing::ui_test_example(env!("CARGO_PKG_NAME"), "ui");
 }
+
+#[test]
+fn fix() {
+    dylint_testing::fix::Test::new(env!("CARGO_PKG_NAME"), "fix/fixture", "fix/expected").run();
+}
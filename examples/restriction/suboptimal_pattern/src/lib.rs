@@ -80,6 +80,8 @@ struct Config {
     explicit_deref_check: bool,
 }
 
+dylint_linting::config_schema!(Config);
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -58,7 +58,8 @@ dylint_linting::declare_late_lint! {
 
     pub ARG_ITER,
     Warn,
-    "functions taking `Iterator` trait bounds when `IntoIterator` would be more flexible"
+    "functions taking `Iterator` trait bounds when `IntoIterator` would be more flexible",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for ArgIter {
@@ -49,7 +49,8 @@ dylint_linting::declare_late_lint! {
     /// ```
     pub COMMENTED_OUT_CODE,
     Warn,
-    "code that has been commented out"
+    "code that has been commented out",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for CommentedOutCode {
@@ -51,7 +51,8 @@ dylint_linting::declare_late_lint! {
     /// ```
     pub DIR_ENTRY_PATH_FILE_NAME,
     Warn,
-    "calling `.path().file_name()` on a DirEntry"
+    "calling `.path().file_name()` on a DirEntry",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for DirEntryPathFileName {
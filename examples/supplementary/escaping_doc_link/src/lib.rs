@@ -42,6 +42,7 @@ dylint_linting::impl_late_lint! {
     pub ESCAPING_DOC_LINK,
     Warn,
     "doc comment links that escape their packages",
+    group = "dylint_supplementary",
     EscapingDocLink::default()
 }
 
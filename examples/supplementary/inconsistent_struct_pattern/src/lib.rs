@@ -48,7 +48,8 @@ dylint_linting::declare_late_lint! {
     /// ```
     pub INCONSISTENT_STRUCT_PATTERN,
     Warn,
-    "struct patterns whose fields do not match their declared order"
+    "struct patterns whose fields do not match their declared order",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for InconsistentStructPattern {
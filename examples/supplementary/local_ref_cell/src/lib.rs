@@ -36,7 +36,8 @@ dylint_linting::declare_late_lint! {
     /// [`RefCell`]: https://doc.rust-lang.org/std/cell/struct.RefCell.html
     pub LOCAL_REF_CELL,
     Warn,
-    "`RefCell` local variables"
+    "`RefCell` local variables",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for LocalRefCell {
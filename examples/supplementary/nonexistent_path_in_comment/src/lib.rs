@@ -56,7 +56,8 @@ dylint_linting::declare_late_lint! {
     /// ```
     pub NONEXISTENT_PATH_IN_COMMENT,
     Warn,
-    "file paths in comments that do not exist"
+    "file paths in comments that do not exist",
+    group = "dylint_supplementary"
 }
 
 // smoelius: Require at least two '/' to consider a string a path.
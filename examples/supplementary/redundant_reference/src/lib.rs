@@ -91,6 +91,7 @@ dylint_linting::impl_late_lint! {
     pub REDUNDANT_REFERENCE,
     Warn,
     "reference fields used only to read one copyable subfield",
+    group = "dylint_supplementary",
     RedundantReference::new()
 }
 
@@ -99,6 +100,8 @@ struct Config {
     lifetime_check: bool,
 }
 
+dylint_linting::config_schema!(Config);
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -3,6 +3,9 @@
 
 dylint_linting::dylint_library!();
 
+// smoelius: Please keep the following arguments sorted by crate name.
+dylint_linting::config_schemas!(redundant_reference, unnamed_constant);
+
 extern crate rustc_lint;
 extern crate rustc_session;
 
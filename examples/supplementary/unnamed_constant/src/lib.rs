@@ -43,6 +43,7 @@ dylint_linting::impl_late_lint! {
     pub UNNAMED_CONSTANT,
     Warn,
     "unnamed constants, aka magic numbers",
+    group = "dylint_supplementary",
     UnnamedConstant::new()
 }
 
@@ -51,6 +52,8 @@ struct Config {
     threshold: u64,
 }
 
+dylint_linting::config_schema!(Config);
+
 impl Default for Config {
     fn default() -> Self {
         Self { threshold: 10 }
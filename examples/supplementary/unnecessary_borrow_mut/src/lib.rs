@@ -54,7 +54,8 @@ dylint_linting::declare_late_lint! {
     /// [`RefCell::borrow`]: https://doc.rust-lang.org/std/cell/struct.RefCell.html#method.borrow
     pub UNNECESSARY_BORROW_MUT,
     Warn,
-    "calls to `RefCell::borrow_mut` that could be `RefCell::borrow`"
+    "calls to `RefCell::borrow_mut` that could be `RefCell::borrow`",
+    group = "dylint_supplementary"
 }
 
 impl<'tcx> LateLintPass<'tcx> for UnnecessaryBorrowMut {
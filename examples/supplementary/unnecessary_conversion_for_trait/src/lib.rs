@@ -71,6 +71,7 @@ dylint_linting::impl_late_lint! {
     pub UNNECESSARY_CONVERSION_FOR_TRAIT,
     Warn,
     "unnecessary calls that preserve trait behavior",
+    group = "dylint_supplementary",
     UnnecessaryConversionForTrait::default()
 }
 
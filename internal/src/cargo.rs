@@ -48,6 +48,7 @@ pub struct Builder {
     description: String,
     quiet: Quiet,
     stable: bool,
+    offline: bool,
 }
 
 #[must_use]
@@ -98,6 +99,7 @@ impl Builder {
             description: description.to_owned(),
             quiet: Quiet::empty(),
             stable: false,
+            offline: false,
         }
     }
 
@@ -120,6 +122,13 @@ impl Builder {
         self
     }
 
+    /// Whether to pass `--offline` to the command.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn offline(&mut self, value: bool) -> &mut Self {
+        self.offline = value;
+        self
+    }
+
     /// Consumes the builder and returns a [`std::process::Command`].
     #[allow(clippy::needless_pass_by_ref_mut)]
     pub fn build(&mut self) -> Command {
@@ -147,6 +156,9 @@ impl Builder {
             command.envs(vec![(crate::env::PATH, new_path)]);
         }
         command.args([&self.subcommand]);
+        if self.offline {
+            command.arg("--offline");
+        }
         if self.quiet.contains(Quiet::STDERR) {
             command.stderr(Stdio::null());
         }
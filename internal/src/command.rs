@@ -52,6 +52,14 @@ impl CommandExt for Command {
     }
 }
 
+/// The version of the interface between `dylint` and `dylint_driver`.
+///
+/// The interface includes, e.g., the format in which the driver lists lints. The driver reports the
+/// version as build metadata in its `-V` output (e.g., `5.0.0+protocol.1`). It should be
+/// incremented whenever the interface changes, so that drivers built before the change are rebuilt
+/// even though their version is unchanged.
+pub const DRIVER_PROTOCOL_VERSION: u64 = 1;
+
 #[allow(unused_variables)]
 pub fn driver(toolchain: &str, driver: &Path) -> Result<Command> {
     #[allow(unused_mut)]
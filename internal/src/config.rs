@@ -1,4 +1,9 @@
-use std::{fs::read_to_string, sync::OnceLock};
+use serde::Deserialize;
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 use thiserror::Error as ThisError;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -61,22 +66,7 @@ pub fn try_init_with_metadata(metadata: &cargo_metadata::Metadata) -> Result<Opt
 
     let cargo_metadata::Metadata { workspace_root, .. } = metadata;
 
-    let dylint_toml = workspace_root.join("dylint.toml");
-
-    let value = if dylint_toml
-        .try_exists()
-        .map_err(|error| Inner::Io(format!("`try_exists` failed for {dylint_toml:?}"), error))?
-    {
-        let value = read_to_string(&dylint_toml).map_err(|error| {
-            Inner::Io(
-                format!("`read_to_string` failed for {dylint_toml:?}"),
-                error,
-            )
-        })?;
-        Some(value)
-    } else {
-        None
-    };
+    let value = read_dylint_toml(workspace_root.as_std_path())?;
 
     if let Some(s) = &value {
         init_from_string(s)?;
@@ -85,21 +75,183 @@ pub fn try_init_with_metadata(metadata: &cargo_metadata::Metadata) -> Result<Opt
     Ok(value)
 }
 
+/// Like [`try_init_with_metadata`], but resolves the configuration for the package whose manifest
+/// is in `manifest_dir`. The following are merged, with later ones taking precedence:
+///
+/// - the `dylint.toml` file in the workspace root
+/// - the `dylint.toml` file in `manifest_dir`
+/// - the package's `[package.metadata.dylint]` table
+///
+/// Tables are merged recursively. Any other value replaces the one it overrides.
+///
+/// Returns the paths of the `dylint.toml` files that were read.
+pub fn try_init_with_metadata_for_package(
+    metadata: &cargo_metadata::Metadata,
+    manifest_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    if CONFIG_TABLE.get().is_some() {
+        return Ok(Vec::new());
+    }
+
+    let cargo_metadata::Metadata {
+        workspace_root,
+        packages,
+        ..
+    } = metadata;
+
+    let mut table = toml::value::Table::new();
+    let mut paths = Vec::new();
+
+    let mut dirs = vec![workspace_root.as_std_path()];
+    if manifest_dir != workspace_root.as_std_path() {
+        dirs.push(manifest_dir);
+    }
+
+    for dir in dirs {
+        if let Some(s) = read_dylint_toml(dir)? {
+            merge(&mut table, parse_table(&s)?);
+            paths.push(dir.join("dylint.toml"));
+        }
+    }
+
+    let package = packages.iter().find(|package| {
+        package
+            .manifest_path
+            .parent()
+            .is_some_and(|parent| parent.as_std_path() == manifest_dir)
+    });
+
+    if let Some(package_table) = package.map(package_metadata_table).transpose()?.flatten() {
+        merge(&mut table, package_table);
+    }
+
+    init_from_table(table);
+
+    Ok(paths)
+}
+
+/// Returns every source of Dylint configuration in the workspace described by `metadata`.
+///
+/// Each source is paired with a description of where it was found. The sources are the
+/// `dylint.toml` files in the workspace root and in the packages' directories, and the packages'
+/// `[package.metadata.dylint]` tables.
+///
+/// Unlike [`try_init_with_metadata_for_package`], this function does not merge the sources.
+pub fn sources(metadata: &cargo_metadata::Metadata) -> Result<Vec<(String, toml::value::Table)>> {
+    let cargo_metadata::Metadata {
+        workspace_root,
+        packages,
+        ..
+    } = metadata;
+
+    let mut sources = Vec::new();
+
+    if let Some(s) = read_dylint_toml(workspace_root.as_std_path())? {
+        let path = workspace_root.join("dylint.toml");
+        sources.push((path.to_string(), parse_table(&s)?));
+    }
+
+    for package in packages {
+        let Some(dir) = package.manifest_path.parent() else {
+            continue;
+        };
+        if dir != workspace_root.as_path()
+            && let Some(s) = read_dylint_toml(dir.as_std_path())?
+        {
+            let path = dir.join("dylint.toml");
+            sources.push((path.to_string(), parse_table(&s)?));
+        }
+        if let Some(table) = package_metadata_table(package)? {
+            sources.push((
+                format!("`[package.metadata.dylint]` in {}", package.manifest_path),
+                table,
+            ));
+        }
+    }
+
+    Ok(sources)
+}
+
+fn read_dylint_toml(dir: &Path) -> Result<Option<String>> {
+    let dylint_toml = dir.join("dylint.toml");
+
+    if !dylint_toml.try_exists().map_err(|error| {
+        Inner::Io(
+            format!(
+                "`try_exists` failed for `{}`",
+                dylint_toml.to_string_lossy()
+            ),
+            error,
+        )
+    })? {
+        return Ok(None);
+    }
+
+    let value = read_to_string(&dylint_toml).map_err(|error| {
+        Inner::Io(
+            format!(
+                "`read_to_string` failed for `{}`",
+                dylint_toml.to_string_lossy()
+            ),
+            error,
+        )
+    })?;
+
+    Ok(Some(value))
+}
+
+fn package_metadata_table(package: &cargo_metadata::Package) -> Result<Option<toml::value::Table>> {
+    let Some(value) = package.metadata.get("dylint") else {
+        return Ok(None);
+    };
+
+    let value = toml::Value::deserialize(value).map_err(|error| {
+        Inner::Other(format!(
+            "Could not convert `package.metadata.dylint` to toml: {error}"
+        ))
+    })?;
+    let toml::Value::Table(table) = value else {
+        return Err(Inner::Other("`package.metadata.dylint` is not a table".into()).into());
+    };
+
+    Ok(Some(table))
+}
+
+fn merge(base: &mut toml::value::Table, overrides: toml::value::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge(base, overrides);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 pub fn init_from_string(s: &str) -> Result<()> {
     assert!(CONFIG_TABLE.get().is_none());
 
+    let table = parse_table(s)?;
+
+    init_from_table(table);
+
+    Ok(())
+}
+
+fn parse_table(s: &str) -> Result<toml::value::Table> {
     let toml: toml::Value = toml::from_str(s)?;
 
-    let table = toml
-        .as_table()
+    toml.as_table()
         .cloned()
-        .ok_or_else(|| Inner::Other("Value is not a table".into()))?;
+        .ok_or_else(|| Inner::Other("Value is not a table".into()).into())
+}
 
-    // smoelius: Rewrite this function (`init_from_string`) and eliminate the next `expect` once
+fn init_from_table(table: toml::value::Table) {
+    // smoelius: Rewrite this function (`init_from_table`) and eliminate the next `expect` once
     // `get_or_try_init` stabilizes: https://github.com/rust-lang/rust/issues/109737
     CONFIG_TABLE
         .set(table)
         .unwrap_or_else(|error| panic!("`CONFIG_TABLE` was determined to be unset above: {error}"));
-
-    Ok(())
 }
@@ -11,6 +11,7 @@ declare_const!(CARGO_CRATE_NAME);
 declare_const!(CARGO_HOME);
 declare_const!(CARGO_INCREMENTAL);
 declare_const!(CARGO_MANIFEST_DIR);
+declare_const!(CARGO_NET_OFFLINE);
 declare_const!(CARGO_PKG_NAME);
 declare_const!(CARGO_PRIMARY_PACKAGE);
 declare_const!(CARGO_TARGET_DIR);
@@ -19,9 +20,12 @@ declare_const!(CI);
 declare_const!(CLIPPY_DISABLE_DOCS_LINKS);
 declare_const!(CLIPPY_DRIVER_PATH);
 declare_const!(DOCS_RS);
+declare_const!(DYLINT_BLESS);
+declare_const!(DYLINT_CONFIG_SCHEMAS);
 declare_const!(DYLINT_DRIVER_PATH);
 declare_const!(DYLINT_LIBRARY_PATH);
 declare_const!(DYLINT_LIBS);
+declare_const!(DYLINT_LINTS);
 declare_const!(DYLINT_LIST);
 declare_const!(DYLINT_METADATA);
 declare_const!(DYLINT_NO_DEPS);
@@ -1,8 +1,10 @@
 use crate::CommandExt;
-use anyhow::{Context, Result};
-use git2::Repository;
+use anyhow::{Context, Result, anyhow};
+use git2::{DiffOptions, Repository};
 use std::{
-    path::Path,
+    collections::BTreeMap,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
@@ -80,3 +82,73 @@ pub fn checkout(repository: &Repository, refname: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Returns the id of the commit checked out in the repository at `path`
+pub fn head_commit(path: &Path) -> Result<String> {
+    let repository = Repository::open(path)
+        .with_context(|| format!("Could not open repository at `{}`", path.display()))?;
+    let commit = repository
+        .head()
+        .and_then(|reference| reference.peel_to_commit())
+        .with_context(|| format!("Could not get HEAD commit of `{}`", path.display()))?;
+    Ok(commit.id().to_string())
+}
+
+/// Returns the lines that differ between `refname` and the working tree.
+///
+/// The working tree is that of the repository containing `path`. Untracked files are included. The
+/// map's keys are absolute paths, and the ranges hold 1-based line numbers.
+pub fn changed_lines(
+    path: &Path,
+    refname: &str,
+) -> Result<BTreeMap<PathBuf, Vec<RangeInclusive<usize>>>> {
+    let repository = Repository::discover(path)
+        .with_context(|| format!("Could not find repository containing `{}`", path.display()))?;
+    let workdir = repository
+        .workdir()
+        .ok_or_else(|| anyhow!("Repository has no working directory"))?;
+    let workdir = workdir
+        .canonicalize()
+        .with_context(|| format!("Could not canonicalize `{}`", workdir.display()))?;
+
+    let tree = repository
+        .revparse_single(refname)
+        .and_then(|object| object.peel_to_tree())
+        .with_context(|| format!("Could not resolve `{refname}` to a tree"))?;
+
+    let mut options = DiffOptions::new();
+    options
+        .context_lines(0)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+
+    let diff = repository
+        .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))
+        .with_context(|| format!("Could not diff `{refname}` against the working tree"))?;
+
+    let mut changed_lines = BTreeMap::<_, Vec<_>>::new();
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |delta, hunk| {
+            // smoelius: A hunk with no new lines is a pure deletion. There is nothing to report
+            // on.
+            if hunk.new_lines() == 0 {
+                return true;
+            }
+            if let Some(path) = delta.new_file().path() {
+                let start = hunk.new_start() as usize;
+                let end = start + hunk.new_lines() as usize - 1;
+                changed_lines
+                    .entry(workdir.join(path))
+                    .or_default()
+                    .push(start..=end);
+            }
+            true
+        }),
+        None,
+    )?;
+
+    Ok(changed_lines)
+}
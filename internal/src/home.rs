@@ -8,3 +8,12 @@ pub fn cargo_home() -> Option<PathBuf> {
         env::home_dir().map(|path| path.join(".cargo"))
     }
 }
+
+#[must_use]
+pub fn rustup_home() -> Option<PathBuf> {
+    if let Ok(rustup_home) = env::var(crate::env::RUSTUP_HOME) {
+        Some(PathBuf::from(rustup_home))
+    } else {
+        env::home_dir().map(|path| path.join(".rustup"))
+    }
+}
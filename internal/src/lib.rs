@@ -33,6 +33,9 @@ pub use git2;
 #[cfg(feature = "home")]
 pub mod home;
 
+#[cfg(feature = "list")]
+pub mod list;
+
 #[cfg(all(nightly, feature = "match_def_path"))]
 mod match_def_path;
 #[cfg(all(nightly, feature = "match_def_path"))]
@@ -0,0 +1,29 @@
+//! Types exchanged between `dylint` and `dylint_driver` when listing lints
+//!
+//! When `DYLINT_LIST` is enabled, the driver writes a JSON-encoded `Vec<Lint>` to standard output
+//! and exits. `dylint` parses that output and renders it as text or JSON.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct Lint {
+    /// The lint's name in lowercase, e.g., `crate_wide_allow`
+    pub name: String,
+    /// The lint's default level, e.g., `warn`
+    pub level: String,
+    /// The lint's description
+    pub description: String,
+    /// The kind of lint pass that checks the lint, if it could be determined
+    pub pass_kind: Option<PassKind>,
+    /// The lint groups that include the lint, e.g., `dylint_general`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PassKind {
+    PreExpansion,
+    Early,
+    Late,
+}
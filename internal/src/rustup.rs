@@ -56,6 +56,24 @@ fn parse_active_toolchain(active: &str) -> Result<String> {
         .ok_or_else(|| anyhow!("Could not determine active toolchain"))
 }
 
+pub fn installed_toolchains() -> Result<Vec<String>> {
+    let output = Command::new("rustup")
+        .sanitize_environment()
+        .args(["toolchain", "list"])
+        .logged_output(true)?;
+    let stdout = std::str::from_utf8(&output.stdout)?;
+
+    Ok(parse_toolchain_list(stdout))
+}
+
+// smoelius: Each line is a toolchain name, possibly followed by an annotation like `(default)`.
+fn parse_toolchain_list(list: &str) -> Vec<String> {
+    list.lines()
+        .filter_map(|line| line.split_ascii_whitespace().next())
+        .map(str::to_owned)
+        .collect()
+}
+
 pub fn toolchain_path(path: &Path) -> Result<PathBuf> {
     let output = Command::new("rustup")
         .sanitize_environment()
@@ -78,7 +96,7 @@ pub fn is_rustc<T: AsRef<OsStr> + ?Sized>(arg: &T) -> bool {
 #[cfg(test)]
 mod rustup_test {
 
-    use crate::rustup::{is_rustc, parse_active_toolchain};
+    use crate::rustup::{is_rustc, parse_active_toolchain, parse_toolchain_list};
 
     #[test]
     fn rustc_is_rustc() {
@@ -106,6 +124,22 @@ mod rustup_test {
             assert_eq!(parse_active_toolchain(output).unwrap(), *expect);
         }
     }
+
+    #[test]
+    fn test_parse_toolchain_list() {
+        let output = "stable-x86_64-unknown-linux-gnu (default)
+nightly-2025-09-18-x86_64-unknown-linux-gnu (active)
+nightly-x86_64-unknown-linux-gnu
+";
+        assert_eq!(
+            parse_toolchain_list(output),
+            [
+                "stable-x86_64-unknown-linux-gnu",
+                "nightly-2025-09-18-x86_64-unknown-linux-gnu",
+                "nightly-x86_64-unknown-linux-gnu",
+            ]
+        );
+    }
 }
 
 // smoelius: I do not know what the right/best way to parse a toolchain is. `parse_toolchain` does
@@ -3,6 +3,8 @@ const COMPONENTS: &[&str] = &["llvm-tools-preview", "rustc-dev"];
 fn main() {
     check_components();
 
+    emit_rustc_info();
+
     #[cfg(docsrs)]
     add_components();
 }
@@ -34,6 +36,25 @@ fn check_components() {
     assert_eq!(COMPONENTS, components);
 }
 
+// smoelius: `dylint_library!` exports the commit hash and toolchain of the rustc that built the
+// library, so that the driver can refuse to load a library built by a different rustc.
+fn emit_rustc_info() {
+    use std::{env, process::Command};
+
+    let rustc = env::var_os("RUSTC").unwrap();
+    let output = Command::new(rustc).arg("-vV").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let commit_hash = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("commit-hash: "))
+        .unwrap_or("unknown");
+    let toolchain = env::var("RUSTUP_TOOLCHAIN").unwrap_or_default();
+
+    println!("cargo:rustc-env=DYLINT_RUSTC_COMMIT_HASH={commit_hash}");
+    println!("cargo:rustc-env=DYLINT_RUSTUP_TOOLCHAIN={toolchain}");
+}
+
 #[cfg(docsrs)]
 fn add_components() {
     for component in COMPONENTS {
@@ -6,6 +6,7 @@
 //! - [`dylint_library!`]
 //! - [`declare_late_lint!`, `declare_early_lint!`, `declare_pre_expansion_lint!`]
 //! - [`impl_late_lint!`, `impl_early_lint!`, `impl_pre_expansion_lint!`]
+//! - [Lint groups]
 //! - [`constituent` feature]
 //! - [Configurable libraries]
 //!
@@ -23,8 +24,26 @@
 //!         .unwrap()
 //!         .into_raw()
 //! }
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn dylint_rustc_commit_hash() -> *mut std::os::raw::c_char {
+//!     std::ffi::CString::new($crate::RUSTC_COMMIT_HASH)
+//!         .unwrap()
+//!         .into_raw()
+//! }
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn dylint_toolchain() -> *mut std::os::raw::c_char {
+//!     std::ffi::CString::new($crate::RUSTUP_TOOLCHAIN)
+//!         .unwrap()
+//!         .into_raw()
+//! }
 //! ```
 //!
+//! The driver checks `dylint_rustc_commit_hash` before calling into the library. A library built by
+//! a different rustc than the driver is rejected with an error naming both toolchains, rather than
+//! crashing the driver.
+//!
 //! If your library uses the `dylint_library!` macro and the [`dylint-link`] tool, then all you
 //! should have to do is implement the [`register_lints`] function. See the [examples] in this
 //! repository.
@@ -77,6 +96,34 @@
 //!                                                ^^^
 //! ```
 //!
+//! # Lint groups
+//!
+//! `declare_late_lint!`, `impl_late_lint!`, etc. accept an optional `group = "..."` argument after
+//! the description. The generated `register_lints` function then adds the lint to the named group,
+//! so that the group can be used with `--warn`, `--allow`, etc. For example:
+//!
+//! ```rust,ignore
+//! dylint_linting::declare_late_lint! {
+//!     pub NAME,
+//!     Warn,
+//!     "description",
+//!     group = "my_lints"
+//! }
+//! ```
+//!
+//! For `impl_late_lint!`, etc., the `group` argument comes before the `LintPass` value.
+//!
+//! A library that registers its lints by hand can call [`add_to_group`] from its own
+//! `register_lints` function.
+//!
+//! Dylint's driver registers each group once, after all libraries have been loaded. So several
+//! libraries can add lints to the same group. For example, loading the `abs_home_path` and
+//! `non_thread_safe_call_in_test` libraries together produces one `dylint_general` group containing
+//! both libraries' lints. Similarly, a library that includes constituents (see below) reports its
+//! constituents' groups, as the [`general` library] does.
+//!
+//! `cargo dylint list` shows each group alongside the lints it contains.
+//!
 //! # `constituent` feature
 //!
 //! Enabling the package-level `constituent` feature changes the way the above macros work.
@@ -110,6 +157,11 @@
 //! - [`init_config`]
 //! - [`try_init_config`]
 //!
+//! A package within a workspace can override the workspace's configuration. The configuration for
+//! the package being compiled is the workspace root's `dylint.toml` file, merged with the package's
+//! own `dylint.toml` file (if any), merged with the package's `[package.metadata.dylint]` table (if
+//! any). Tables are merged recursively, and any other value replaces the one it overrides.
+//!
 //! A configurable library containing just one lint will typically have a `lib.rs` file of the
 //! following form:
 //!
@@ -162,12 +214,28 @@
 //! }
 //! ```
 //!
+//! A configurable library can also export a [JSON Schema] for its configuration type by calling
+//! [`config_schema!`] with the type. Doing so allows `cargo dylint config --validate` to report
+//! unknown keys and type mismatches in `dylint.toml` files before a check is run:
+//!
+//! ```rust,ignore
+//! dylint_linting::config_schema!(Config);
+//! ```
+//!
+//! A library that includes constituents should instead call [`config_schemas!`] with the names of
+//! the constituents that call `config_schema!`.
+//!
 //! Additional documentation on `config_or_default`, etc. can be found on [docs.rs].
 //!
 //! [Configurable libraries]: #configurable-libraries
 //! [Dylint]: https://github.com/trailofbits/dylint/tree/master
+//! [JSON Schema]: https://json-schema.org/
+//! [Lint groups]: #lint-groups
 //! [`LintPass`]: https://doc.rust-lang.org/nightly/nightly-rustc/rustc_lint/trait.LintPass.html
+//! [`add_to_group`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.add_to_group.html
 //! [`config_or_default`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_or_default.html
+//! [`config_schema!`]: https://docs.rs/dylint_linting/latest/dylint_linting/macro.config_schema.html
+//! [`config_schemas!`]: https://docs.rs/dylint_linting/latest/dylint_linting/macro.config_schemas.html
 //! [`config_toml`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config_toml.html
 //! [`config`]: https://docs.rs/dylint_linting/latest/dylint_linting/fn.config.html
 //! [`constituent` feature]: #constituent-feature
@@ -198,20 +266,37 @@
 #[allow(unused_extern_crates)]
 extern crate rustc_driver;
 
+extern crate rustc_lint;
 extern crate rustc_session;
 extern crate rustc_span;
 
 use dylint_internal::{config, env};
+use rustc_session::lint::{Lint, LintId};
 use rustc_span::Symbol;
 use std::{
     any::type_name,
+    collections::BTreeMap,
     path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
 };
 
 pub use config::{Error as ConfigError, Result as ConfigResult};
 
+mod schema;
+pub use schema::config_schema;
+#[doc(hidden)]
+pub use schema::object as __config_schemas_object;
+
 pub const DYLINT_VERSION: &str = "0.1.0";
 
+/// The commit hash of the rustc that built this crate, or `unknown`
+#[doc(hidden)]
+pub const RUSTC_COMMIT_HASH: &str = env!("DYLINT_RUSTC_COMMIT_HASH");
+
+/// The rustup toolchain that built this crate, or the empty string if it could not be determined
+#[doc(hidden)]
+pub const RUSTUP_TOOLCHAIN: &str = env!("DYLINT_RUSTUP_TOOLCHAIN");
+
 pub use paste;
 
 // smoelius: Including `extern crate rustc_driver` causes the library to link against
@@ -230,6 +315,66 @@ macro_rules! dylint_library {
                 .unwrap()
                 .into_raw()
         }
+
+        #[doc(hidden)]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn dylint_rustc_commit_hash() -> *mut std::os::raw::c_char {
+            std::ffi::CString::new($crate::RUSTC_COMMIT_HASH)
+                .unwrap()
+                .into_raw()
+        }
+
+        #[doc(hidden)]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn dylint_toolchain() -> *mut std::os::raw::c_char {
+            std::ffi::CString::new($crate::RUSTUP_TOOLCHAIN)
+                .unwrap()
+                .into_raw()
+        }
+
+        #[doc(hidden)]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn dylint_lint_groups() -> *mut std::os::raw::c_char {
+            std::ffi::CString::new($crate::__lint_groups())
+                .unwrap()
+                .into_raw()
+        }
+    };
+}
+
+/// Exports a JSON Schema for a library's configuration type so that `cargo dylint config` can
+/// validate `dylint.toml` files. The argument should be the type passed to `config_or_default`,
+/// `config`, etc.
+///
+/// With the `constituent` feature enabled, the schema is not exported. Rather, the library that
+/// includes the constituent should export the schema by calling [`config_schemas!`].
+#[macro_export]
+macro_rules! config_schema {
+    ($ty:ty) => {
+        #[doc(hidden)]
+        pub fn config_schema() -> (&'static str, String) {
+            (env!("CARGO_PKG_NAME"), $crate::config_schema::<$ty>())
+        }
+
+        $crate::__maybe_exclude! {
+            $crate::config_schemas!(self);
+        }
+    };
+}
+
+/// Exports the JSON Schemas of the configuration types of a library's constituents. Each argument
+/// should be the name of a crate that calls [`config_schema!`].
+#[macro_export]
+macro_rules! config_schemas {
+    ($($krate:ident),* $(,)?) => {
+        #[doc(hidden)]
+        #[unsafe(no_mangle)]
+        pub extern "C" fn dylint_config_schemas() -> *mut std::os::raw::c_char {
+            let schemas = [$($krate::config_schema()),*];
+            std::ffi::CString::new($crate::__config_schemas_object(&schemas))
+                .unwrap()
+                .into_raw()
+        }
     };
 }
 
@@ -271,7 +416,7 @@ macro_rules! __maybe_mangle {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __declare_and_register_lint {
-    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, $register_pass_method:ident, $pass:expr) => {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, [$($group:literal)?], $register_pass_method:ident, $pass:expr) => {
         $crate::__maybe_exclude! {
             $crate::dylint_library!();
         }
@@ -285,6 +430,9 @@ macro_rules! __declare_and_register_lint {
                 $crate::init_config(sess);
                 lint_store.register_lints(&[$NAME]);
                 lint_store.$register_pass_method($pass);
+                $(
+                    $crate::add_to_group($group, $NAME);
+                )?
             }
         }
 
@@ -315,11 +463,25 @@ macro_rules! __make_late_closure {
 
 #[macro_export]
 macro_rules! impl_pre_expansion_lint {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, group = $group:literal, $pass:expr) => {
+        $crate::__declare_and_register_lint!(
+            $(#[$attr])* $vis $NAME,
+            $Level,
+            $desc,
+            [$group],
+            register_pre_expansion_pass,
+            || Box::new($pass)
+        );
+        $crate::paste::paste! {
+            rustc_session::impl_lint_pass!([< $NAME:camel >] => [$NAME]);
+        }
+    };
     ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, $pass:expr) => {
         $crate::__declare_and_register_lint!(
             $(#[$attr])* $vis $NAME,
             $Level,
             $desc,
+            [],
             register_pre_expansion_pass,
             || Box::new($pass)
         );
@@ -331,11 +493,25 @@ macro_rules! impl_pre_expansion_lint {
 
 #[macro_export]
 macro_rules! impl_early_lint {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, group = $group:literal, $pass:expr) => {
+        $crate::__declare_and_register_lint!(
+            $(#[$attr])* $vis $NAME,
+            $Level,
+            $desc,
+            [$group],
+            register_early_pass,
+            || Box::new($pass)
+        );
+        $crate::paste::paste! {
+            rustc_session::impl_lint_pass!([< $NAME:camel >] => [$NAME]);
+        }
+    };
     ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, $pass:expr) => {
         $crate::__declare_and_register_lint!(
             $(#[$attr])* $vis $NAME,
             $Level,
             $desc,
+            [],
             register_early_pass,
             || Box::new($pass)
         );
@@ -347,11 +523,25 @@ macro_rules! impl_early_lint {
 
 #[macro_export]
 macro_rules! impl_late_lint {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, group = $group:literal, $pass:expr) => {
+        $crate::__declare_and_register_lint!(
+            $(#[$attr])* $vis $NAME,
+            $Level,
+            $desc,
+            [$group],
+            register_late_pass,
+            $crate::__make_late_closure!($pass)
+        );
+        $crate::paste::paste! {
+            rustc_session::impl_lint_pass!([< $NAME:camel >] => [$NAME]);
+        }
+    };
     ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr, $pass:expr) => {
         $crate::__declare_and_register_lint!(
             $(#[$attr])* $vis $NAME,
             $Level,
             $desc,
+            [],
             register_late_pass,
             $crate::__make_late_closure!($pass)
         );
@@ -363,12 +553,13 @@ macro_rules! impl_late_lint {
 
 #[macro_export]
 macro_rules! declare_pre_expansion_lint {
-    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr) => {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr $(, group = $group:literal)?) => {
         $crate::paste::paste! {
             $crate::__declare_and_register_lint!(
                 $(#[$attr])* $vis $NAME,
                 $Level,
                 $desc,
+                [$($group)?],
                 register_pre_expansion_pass,
                 || Box::new([< $NAME:camel >])
             );
@@ -379,12 +570,13 @@ macro_rules! declare_pre_expansion_lint {
 
 #[macro_export]
 macro_rules! declare_early_lint {
-    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr) => {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr $(, group = $group:literal)?) => {
         $crate::paste::paste! {
             $crate::__declare_and_register_lint!(
                 $(#[$attr])* $vis $NAME,
                 $Level,
                 $desc,
+                [$($group)?],
                 register_early_pass,
                 || Box::new([< $NAME:camel >])
             );
@@ -395,12 +587,13 @@ macro_rules! declare_early_lint {
 
 #[macro_export]
 macro_rules! declare_late_lint {
-    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr) => {
+    ($(#[$attr:meta])* $vis:vis $NAME:ident, $Level:ident, $desc:expr $(, group = $group:literal)?) => {
         $crate::paste::paste! {
             $crate::__declare_and_register_lint!(
                 $(#[$attr])* $vis $NAME,
                 $Level,
                 $desc,
+                [$($group)?],
                 register_late_pass,
                 $crate::__make_late_closure!([< $NAME:camel >])
             );
@@ -409,6 +602,44 @@ macro_rules! declare_late_lint {
     };
 }
 
+static GROUPS: Mutex<BTreeMap<&str, Vec<&Lint>>> = Mutex::new(BTreeMap::new());
+
+/// Adds `lint` to the lint group `group`.
+///
+/// The `register_lints` function generated by `impl_late_lint`, etc. calls `add_to_group` when the
+/// macro is passed a `group = "..."` argument.
+///
+/// A library does not register its groups itself. Rather, Dylint's driver registers each group
+/// once, after all libraries have been loaded. This allows libraries to contribute lints to the
+/// same group.
+pub fn add_to_group(group: &'static str, lint: &'static Lint) {
+    GROUPS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(group)
+        .or_default()
+        .push(lint);
+}
+
+/// Returns the groups populated by [`add_to_group`] as a JSON object mapping each group to the
+/// names of its lints
+#[doc(hidden)]
+#[must_use]
+pub fn __lint_groups() -> String {
+    let groups = GROUPS.lock().unwrap_or_else(PoisonError::into_inner);
+    let groups = groups
+        .iter()
+        .map(|(group, lints)| {
+            let names = lints
+                .iter()
+                .map(|lint| LintId::of(lint).to_string())
+                .collect::<Vec<_>>();
+            (*group, names)
+        })
+        .collect::<Vec<_>>();
+    schema::string_lists_object(&groups)
+}
+
 /// Reads and deserializes an entry from the workspace's `dylint.toml` file, and returns the default
 /// value if the entry is not present.
 ///
@@ -506,7 +737,9 @@ impl ParseSess for rustc_session::Session {
     }
 }
 
-/// Reads the target workspace's `dylint.toml` file and parses it as a `toml::value::Table`.
+/// Reads the target workspace's `dylint.toml` file and parses it as a `toml::value::Table`. If the
+/// package being compiled has its own `dylint.toml` file or `[package.metadata.dylint]` table,
+/// they are merged into the result.
 ///
 /// Note: `init_config` or `try_init_config` must be called before `config_or_default`, `config`, or
 /// `config_toml` is called. However, the `register_lints` function generated by `impl_late_lint`,
@@ -593,13 +826,29 @@ fn try_init_config_guarded(sess: &rustc_session::Session) -> ConfigResult<()> {
         _ => {
             let metadata = result?;
 
-            let value = config::try_init_with_metadata(&metadata)?;
-
-            if let Some(s) = &value {
-                sess.parse_sess()
-                    .file_depinfo
-                    .lock()
-                    .insert(Symbol::intern(s));
+            // smoelius: Cargo sets `CARGO_MANIFEST_DIR` when compiling a package. If it is not set,
+            // fall back to the workspace's `dylint.toml` file.
+            if let Ok(manifest_dir) = std::env::var(env::CARGO_MANIFEST_DIR) {
+                let paths = config::try_init_with_metadata_for_package(
+                    &metadata,
+                    Path::new(&manifest_dir),
+                )?;
+
+                for path in paths {
+                    sess.parse_sess()
+                        .file_depinfo
+                        .lock()
+                        .insert(Symbol::intern(&path.to_string_lossy()));
+                }
+            } else {
+                let value = config::try_init_with_metadata(&metadata)?;
+
+                if let Some(s) = &value {
+                    sess.parse_sess()
+                        .file_depinfo
+                        .lock()
+                        .insert(Symbol::intern(s));
+                }
             }
         }
     }
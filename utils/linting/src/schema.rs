@@ -0,0 +1,564 @@
+//! JSON Schemas for configuration types
+//!
+//! A configuration type's schema is derived from the type's `Deserialize` implementation. The type
+//! is deserialized from a `Tracer`, a `Deserializer` that records what each `deserialize_*` call
+//! asks for, and answers with a placeholder value. So no derive macro other than `Deserialize` is
+//! needed.
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor, value::BorrowedStrDeserializer,
+};
+use std::fmt::{Display, Formatter, Write};
+
+// smoelius: A recursive type (e.g., one containing an `Option<Box<Self>>`) would otherwise cause
+// the tracer to recurse forever.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Default)]
+enum Schema {
+    #[default]
+    Any,
+    Boolean,
+    Integer {
+        unsigned: bool,
+    },
+    Number,
+    String,
+    Array(Box<Schema>),
+    Map(Box<Schema>),
+    Struct(Vec<(&'static str, Schema)>),
+    Enum(&'static [&'static str]),
+}
+
+#[derive(Debug)]
+struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Returns a JSON Schema describing the values that `T` can be deserialized from
+///
+/// Struct fields are described, but not whether they are required. Unknown struct fields are
+/// disallowed. If `T` cannot be deserialized from placeholder values (e.g., because its
+/// `Deserialize` implementation validates its input), the returned schema accepts any value.
+#[must_use]
+pub fn config_schema<T: DeserializeOwned>() -> String {
+    let mut schema = Schema::default();
+    let result = Tracer::new(&mut schema, 0).and_then(T::deserialize);
+    if result.is_err() {
+        schema = Schema::Any;
+    }
+    let mut json = String::new();
+    write_schema(&mut json, &schema);
+    json
+}
+
+/// Returns a JSON object mapping each name in `schemas` to the accompanying schema
+#[must_use]
+pub fn object(schemas: &[(&str, String)]) -> String {
+    let mut json = String::from("{");
+    for (i, (name, schema)) in schemas.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_string(&mut json, name);
+        json.push(':');
+        json.push_str(schema);
+    }
+    json.push('}');
+    json
+}
+
+/// Returns a JSON object mapping each name in `lists` to the accompanying list of strings
+#[must_use]
+pub fn string_lists_object(lists: &[(&str, Vec<String>)]) -> String {
+    let mut json = String::from("{");
+    for (i, (name, strings)) in lists.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_string(&mut json, name);
+        json.push_str(":[");
+        for (j, s) in strings.iter().enumerate() {
+            if j > 0 {
+                json.push(',');
+            }
+            write_string(&mut json, s);
+        }
+        json.push(']');
+    }
+    json.push('}');
+    json
+}
+
+struct Tracer<'a> {
+    schema: &'a mut Schema,
+    depth: usize,
+}
+
+impl<'a> Tracer<'a> {
+    fn new(schema: &'a mut Schema, depth: usize) -> Result<Self, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error(String::from("type is nested too deeply")));
+        }
+        Ok(Self { schema, depth })
+    }
+
+    fn record(self, schema: Schema) {
+        *self.schema = schema;
+    }
+}
+
+macro_rules! trace_primitive {
+    ($method:ident, $schema:expr, $visit:ident($value:expr)) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            self.record($schema);
+            visitor.$visit($value)
+        }
+    };
+}
+
+impl<'de> Deserializer<'de> for Tracer<'_> {
+    type Error = Error;
+
+    // smoelius: There is no telling what the visitor expects. Offer it a unit and hope for the
+    // best.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    trace_primitive!(deserialize_bool, Schema::Boolean, visit_bool(false));
+    trace_primitive!(
+        deserialize_i8,
+        Schema::Integer { unsigned: false },
+        visit_i64(0)
+    );
+    trace_primitive!(
+        deserialize_i16,
+        Schema::Integer { unsigned: false },
+        visit_i64(0)
+    );
+    trace_primitive!(
+        deserialize_i32,
+        Schema::Integer { unsigned: false },
+        visit_i64(0)
+    );
+    trace_primitive!(
+        deserialize_i64,
+        Schema::Integer { unsigned: false },
+        visit_i64(0)
+    );
+    trace_primitive!(
+        deserialize_i128,
+        Schema::Integer { unsigned: false },
+        visit_i128(0)
+    );
+    trace_primitive!(
+        deserialize_u8,
+        Schema::Integer { unsigned: true },
+        visit_u64(0)
+    );
+    trace_primitive!(
+        deserialize_u16,
+        Schema::Integer { unsigned: true },
+        visit_u64(0)
+    );
+    trace_primitive!(
+        deserialize_u32,
+        Schema::Integer { unsigned: true },
+        visit_u64(0)
+    );
+    trace_primitive!(
+        deserialize_u64,
+        Schema::Integer { unsigned: true },
+        visit_u64(0)
+    );
+    trace_primitive!(
+        deserialize_u128,
+        Schema::Integer { unsigned: true },
+        visit_u128(0)
+    );
+    trace_primitive!(deserialize_f32, Schema::Number, visit_f64(0.0));
+    trace_primitive!(deserialize_f64, Schema::Number, visit_f64(0.0));
+    trace_primitive!(deserialize_char, Schema::String, visit_char('_'));
+    trace_primitive!(deserialize_str, Schema::String, visit_str(""));
+    trace_primitive!(deserialize_string, Schema::String, visit_str(""));
+    trace_primitive!(deserialize_identifier, Schema::String, visit_str(""));
+    trace_primitive!(
+        deserialize_bytes,
+        Schema::Array(Box::new(Schema::Integer { unsigned: true })),
+        visit_bytes(&[])
+    );
+    trace_primitive!(
+        deserialize_byte_buf,
+        Schema::Array(Box::new(Schema::Integer { unsigned: true })),
+        visit_bytes(&[])
+    );
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    // smoelius: TOML has no null, so an `Option` is described by the schema of its contents. A
+    // `None` is expressed by omitting the key.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let depth = self.depth + 1;
+        visitor.visit_some(Tracer::new(self.schema, depth)?)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let depth = self.depth + 1;
+        visitor.visit_newtype_struct(Tracer::new(self.schema, depth)?)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_tuple(1, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let mut items = Schema::default();
+        let value = visitor.visit_seq(SeqTracer {
+            items: &mut items,
+            index: 0,
+            len,
+            depth: self.depth + 1,
+        })?;
+        self.record(Schema::Array(Box::new(items)));
+        Ok(value)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut values = Schema::default();
+        let value = visitor.visit_map(MapTracer {
+            values: &mut values,
+            done: false,
+            depth: self.depth + 1,
+        })?;
+        self.record(Schema::Map(Box::new(values)));
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let mut properties = fields
+            .iter()
+            .map(|&field| (field, Schema::default()))
+            .collect::<Vec<_>>();
+        let value = visitor.visit_map(StructTracer {
+            properties: &mut properties,
+            index: 0,
+            depth: self.depth + 1,
+        })?;
+        self.record(Schema::Struct(properties));
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let Some(&variant) = variants.first() else {
+            return Err(Error(String::from("enum has no variants")));
+        };
+        let value = visitor.visit_enum(EnumTracer {
+            variant,
+            depth: self.depth + 1,
+        })?;
+        self.record(Schema::Enum(variants));
+        Ok(value)
+    }
+}
+
+struct SeqTracer<'a> {
+    items: &'a mut Schema,
+    index: usize,
+    len: usize,
+    depth: usize,
+}
+
+impl<'de> SeqAccess<'de> for SeqTracer<'_> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        self.index += 1;
+        // smoelius: Only the first element's schema is recorded.
+        let mut scratch = Schema::default();
+        let schema = if self.index == 1 {
+            &mut *self.items
+        } else {
+            &mut scratch
+        };
+        seed.deserialize(Tracer::new(schema, self.depth)?).map(Some)
+    }
+}
+
+struct MapTracer<'a> {
+    values: &'a mut Schema,
+    done: bool,
+    depth: usize,
+}
+
+impl<'de> MapAccess<'de> for MapTracer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut scratch = Schema::default();
+        seed.deserialize(Tracer::new(&mut scratch, self.depth)?)
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        self.done = true;
+        seed.deserialize(Tracer::new(&mut *self.values, self.depth)?)
+    }
+}
+
+struct StructTracer<'a> {
+    properties: &'a mut [(&'static str, Schema)],
+    index: usize,
+    depth: usize,
+}
+
+impl<'de> MapAccess<'de> for StructTracer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some(&(field, _)) = self.properties.get(self.index) else {
+            return Ok(None);
+        };
+        seed.deserialize(BorrowedStrDeserializer::<Error>::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (_, schema) = &mut self.properties[self.index];
+        self.index += 1;
+        seed.deserialize(Tracer::new(schema, self.depth)?)
+    }
+}
+
+struct EnumTracer {
+    variant: &'static str,
+    depth: usize,
+}
+
+impl<'de> EnumAccess<'de> for EnumTracer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let value = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+        Ok((value, self))
+    }
+}
+
+// smoelius: The contents of non-unit variants are traced, but not recorded.
+impl<'de> VariantAccess<'de> for EnumTracer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        let mut scratch = Schema::default();
+        seed.deserialize(Tracer::new(&mut scratch, self.depth)?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        let mut scratch = Schema::default();
+        Tracer::new(&mut scratch, self.depth)?.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let mut scratch = Schema::default();
+        Tracer::new(&mut scratch, self.depth)?.deserialize_struct("", fields, visitor)
+    }
+}
+
+fn write_schema(json: &mut String, schema: &Schema) {
+    match schema {
+        Schema::Any => json.push_str("{}"),
+        Schema::Boolean => json.push_str(r#"{"type":"boolean"}"#),
+        Schema::Integer { unsigned: false } => json.push_str(r#"{"type":"integer"}"#),
+        Schema::Integer { unsigned: true } => json.push_str(r#"{"type":"integer","minimum":0}"#),
+        Schema::Number => json.push_str(r#"{"type":"number"}"#),
+        Schema::String => json.push_str(r#"{"type":"string"}"#),
+        Schema::Array(items) => {
+            json.push_str(r#"{"type":"array","items":"#);
+            write_schema(json, items);
+            json.push('}');
+        }
+        Schema::Map(values) => {
+            json.push_str(r#"{"type":"object","additionalProperties":"#);
+            write_schema(json, values);
+            json.push('}');
+        }
+        Schema::Struct(properties) => {
+            json.push_str(r#"{"type":"object","properties":{"#);
+            for (i, (name, schema)) in properties.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_string(json, name);
+                json.push(':');
+                write_schema(json, schema);
+            }
+            json.push_str(r#"},"additionalProperties":false}"#);
+        }
+        // smoelius: A unit variant is written as a string. Any other variant is written as a table
+        // with a single key, the variant's name.
+        Schema::Enum(variants) => {
+            let mut names = String::from("[");
+            for (i, variant) in variants.iter().enumerate() {
+                if i > 0 {
+                    names.push(',');
+                }
+                write_string(&mut names, variant);
+            }
+            names.push(']');
+            write!(
+                json,
+                r#"{{"anyOf":[{{"enum":{names}}},{{"type":"object","propertyNames":{{"enum":{names}}},"minProperties":1,"maxProperties":1}}]}}"#
+            )
+            .unwrap_or_default();
+        }
+    }
+}
+
+fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str(r#"\""#),
+            '\\' => json.push_str(r"\\"),
+            c if c.is_control() => {
+                write!(json, r"\u{:04x}", u32::from(c)).unwrap_or_default();
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    enum Mode {
+        Fast,
+        Thorough { passes: u32 },
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Config {
+        threshold: u64,
+        ratio: Option<f64>,
+        names: Vec<String>,
+        mode: Mode,
+        extra: BTreeMap<String, bool>,
+    }
+
+    #[test]
+    fn string_lists() {
+        assert_eq!(
+            string_lists_object(&[
+                ("a", vec![String::from("x"), String::from("y")]),
+                ("b", Vec::new())
+            ]),
+            r#"{"a":["x","y"],"b":[]}"#
+        );
+    }
+
+    #[test]
+    fn struct_schema() {
+        assert_eq!(
+            config_schema::<Config>(),
+            r#"{"type":"object","properties":{"threshold":{"type":"integer","minimum":0},"ratio":{"type":"number"},"names":{"type":"array","items":{"type":"string"}},"mode":{"anyOf":[{"enum":["fast","thorough"]},{"type":"object","propertyNames":{"enum":["fast","thorough"]},"minProperties":1,"maxProperties":1}]},"extra":{"type":"object","additionalProperties":{"type":"boolean"}}},"additionalProperties":false}"#
+        );
+    }
+
+    #[allow(dead_code)]
+    #[derive(Deserialize)]
+    struct Recursive {
+        next: Option<Box<Recursive>>,
+    }
+
+    #[test]
+    fn recursive_schema_accepts_anything() {
+        assert_eq!(config_schema::<Recursive>(), "{}");
+    }
+
+    #[test]
+    fn object_escapes_names() {
+        assert_eq!(object(&[("a\"b", String::from("{}"))]), r#"{"a\"b":{}}"#);
+    }
+}
@@ -0,0 +1,78 @@
+//! The default backend, based on [`compiletest_rs`]. `compiletest_rs` runs the tests in the current
+//! process's environment. So `DYLINT_TOML` is set for the whole process, and tests are run one at a
+//! time.
+//!
+//! [`compiletest_rs`]: https://github.com/Manishearth/compiletest-rs
+
+use crate::{test_rustc_flags, ui};
+use compiletest_rs as compiletest;
+use dylint_internal::env;
+use std::{
+    env::{remove_var, set_var, var_os},
+    ffi::{OsStr, OsString},
+    path::Path,
+    sync::Mutex,
+};
+
+pub static MUTEX: Mutex<()> = Mutex::new(());
+
+pub fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config, bless: bool) {
+    let _lock = MUTEX.lock().unwrap();
+
+    // smoelius: There doesn't seem to be a way to set environment variables using `compiletest`'s
+    // [`Config`](https://docs.rs/compiletest_rs/0.7.1/compiletest_rs/common/struct.Config.html)
+    // struct. For comparison, where Clippy uses `compiletest`, it sets environment variables
+    // directly (see: https://github.com/rust-lang/rust-clippy/blob/master/tests/compile-test.rs).
+    //
+    // Of course, even if `compiletest` had such support, it would need to be incorporated into
+    // `dylint_testing`.
+    //
+    // smoelius: The `ui_test` backend does not have this problem. See `ui_test_runner.rs`.
+
+    let _var = config
+        .dylint_toml
+        .as_ref()
+        .map(|value| VarGuard::set(env::DYLINT_TOML, value));
+
+    let compiletest_config = compiletest::Config {
+        mode: compiletest::common::Mode::Ui,
+        rustc_path: driver.to_path_buf(),
+        src_base: src_base.to_path_buf(),
+        target_rustcflags: Some(test_rustc_flags(config).join(" ")),
+        bless,
+        ..compiletest::Config::default()
+    };
+
+    compiletest::run_tests(&compiletest_config);
+}
+
+// smoelius: `VarGuard` was copied from:
+// https://github.com/rust-lang/rust-clippy/blob/9cc8da222b3893bc13bc13c8827e93f8ea246854/tests/compile-test.rs
+// smoelius: Clippy dropped `VarGuard` when it switched to `ui_test`:
+// https://github.com/rust-lang/rust-clippy/commit/77d10ac63dae6ef0a691d9acd63d65de9b9bf88e
+
+/// Restores an env var on drop
+#[must_use]
+struct VarGuard {
+    key: &'static str,
+    value: Option<OsString>,
+}
+
+impl VarGuard {
+    fn set(key: &'static str, val: impl AsRef<OsStr>) -> Self {
+        let value = var_os(key);
+        unsafe {
+            set_var(key, val);
+        }
+        Self { key, value }
+    }
+}
+
+impl Drop for VarGuard {
+    fn drop(&mut self) {
+        match self.value.as_deref() {
+            None => unsafe { remove_var(self.key) },
+            Some(value) => unsafe { set_var(self.key, value) },
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-file `dylint.toml` configurations. A test file can specify its configuration with one or
+//! more `//@ dylint-toml: ...` header lines, or with a sibling file whose name ends in
+//! `.dylint.toml` (e.g., `main.dylint.toml` for `main.rs`). Either takes precedence over
+//! [`ui::Test::dylint_toml`].
+//!
+//! Keys must be qualified with a library name (e.g., `library.key = value`). An unqualified key
+//! would be ignored by every library, and so is an error.
+//!
+//! [`ui::Test::dylint_toml`]: crate::ui::Test::dylint_toml
+
+use anyhow::{Context, Result, ensure};
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+const HEADER: &str = "dylint-toml:";
+
+const EXTENSION: &str = "dylint.toml";
+
+/// Returns the `dylint.toml` contents that `path` specifies for itself, if any
+pub fn per_file(path: &Path) -> Result<Option<String>> {
+    let source = read_to_string(path)
+        .with_context(|| format!("`read_to_string` failed for `{}`", path.display()))?;
+    let header = header(&source);
+
+    let sibling = sibling(path);
+    let sibling_exists = sibling
+        .try_exists()
+        .with_context(|| format!("Could not determine whether `{}` exists", sibling.display()))?;
+
+    let dylint_toml = if sibling_exists {
+        ensure!(
+            header.is_none(),
+            "`{}` has a `//@ {HEADER}` header and a sibling `{}`; use one or the other",
+            path.display(),
+            sibling.display()
+        );
+
+        read_to_string(&sibling)
+            .with_context(|| format!("`read_to_string` failed for `{}`", sibling.display()))?
+    } else if let Some(header) = header {
+        header
+    } else {
+        return Ok(None);
+    };
+
+    check_qualified(&dylint_toml).with_context(|| {
+        format!(
+            "Invalid `dylint.toml` configuration for `{}`",
+            path.display()
+        )
+    })?;
+
+    Ok(Some(dylint_toml))
+}
+
+fn check_qualified(dylint_toml: &str) -> Result<()> {
+    let table = dylint_toml
+        .parse::<toml::Table>()
+        .with_context(|| "Could not parse configuration")?;
+
+    for (key, value) in table {
+        ensure!(
+            value.is_table(),
+            "`{key}` is not qualified with a library name (e.g., `library.{key}`)"
+        );
+    }
+
+    Ok(())
+}
+
+fn header(source: &str) -> Option<String> {
+    let lines = source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("//@"))
+        .filter_map(|line| line.trim_start().strip_prefix(HEADER))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+fn sibling(path: &Path) -> PathBuf {
+    path.with_extension(EXTENSION)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn header_lines_are_joined() {
+        let source = "\
+//@ dylint-toml: library.a = true
+//@dylint-toml:library.b = false
+// dylint-toml: library.c = true
+
+fn main() {}
+";
+        assert_eq!(
+            Some("library.a = true\nlibrary.b = false"),
+            header(source).as_deref()
+        );
+        assert_eq!(None, header("fn main() {}\n"));
+    }
+
+    #[test]
+    fn header_and_sibling_conflict() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("main.rs");
+        std::fs::write(&path, "//@ dylint-toml: library.a = true\n").unwrap();
+        std::fs::write(sibling(&path), "library.b = false\n").unwrap();
+
+        let error = per_file(&path).unwrap_err();
+        assert!(
+            error.to_string().contains("use one or the other"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn unqualified_keys_are_rejected() {
+        check_qualified("library.a = true\n[other_library]\nb = false\n").unwrap();
+
+        let error = check_qualified("public_only = true\n").unwrap_err();
+        assert_eq!(
+            "`public_only` is not qualified with a library name (e.g., `library.public_only`)",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Tests of a library's fixes on whole packages.
+//!
+//! A fixture package is copied to a temporary directory, and `cargo dylint --fix` is run on the
+//! copy with the library under test. The resulting package is then compared to an expected
+//! snapshot directory.
+//!
+//! The `target` directory is never compared, and neither is `Cargo.lock` unless the fixture
+//! contains one.
+
+use crate::{copy_dir, initialize, library_path, report_blessed};
+use anyhow::{Context, Result, anyhow, ensure};
+use dylint_internal::env;
+use similar::TextDiff;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{create_dir_all, read, read_dir, remove_file, write},
+    path::{Path, PathBuf},
+};
+
+/// Fix test builder
+pub struct Test {
+    name: String,
+    fixture: PathBuf,
+    expected: PathBuf,
+    args: Vec<String>,
+    bless: bool,
+}
+
+impl Test {
+    /// Test a library's fixes on a package.
+    ///
+    /// - `name` is the name of a Dylint library to be tested.
+    /// - `fixture` is the directory of the package on which to run the library's fixes. The
+    ///   package's `Cargo.toml` should contain an empty `[workspace]` table so that it is not
+    ///   considered part of an enclosing workspace.
+    /// - `expected` is a directory containing what the package should look like after the fixes are
+    ///   applied.
+    #[must_use]
+    pub fn new(name: &str, fixture: impl AsRef<Path>, expected: impl AsRef<Path>) -> Self {
+        Self {
+            name: name.to_owned(),
+            fixture: fixture.as_ref().to_owned(),
+            expected: expected.as_ref().to_owned(),
+            args: Vec::new(),
+            bless: false,
+        }
+    }
+
+    /// Pass arguments to `cargo fix` (e.g., `--all-targets`).
+    pub fn args(&mut self, args: impl IntoIterator<Item = impl AsRef<str>>) -> &mut Self {
+        self.args
+            .extend(args.into_iter().map(|s| s.as_ref().to_owned()));
+        self
+    }
+
+    /// Overwrite the expected directory with the fixed package, rather than failing when they
+    /// differ. Setting the `DYLINT_BLESS` environment variable to a non-zero value has the same
+    /// effect.
+    pub const fn bless(&mut self) -> &mut Self {
+        self.bless = true;
+        self
+    }
+
+    /// Run the test.
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    pub fn run(&mut self) {
+        self.run_immutable().unwrap();
+    }
+
+    fn run_immutable(&self) -> Result<()> {
+        initialize(&self.name).as_ref().unwrap();
+
+        let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+        let package = tempdir.path();
+
+        copy_dir(
+            &self.fixture,
+            package,
+            &BTreeSet::from([self.fixture.join("target")]),
+        )?;
+
+        self.fix(package)?;
+
+        let mut exclude = BTreeSet::from([package.join("target")]);
+        if !self
+            .fixture
+            .join("Cargo.lock")
+            .try_exists()
+            .with_context(|| {
+                format!(
+                    "Could not determine whether `{}` exists",
+                    self.fixture.join("Cargo.lock").display()
+                )
+            })?
+        {
+            exclude.insert(package.join("Cargo.lock"));
+        }
+
+        let actual = files(package, package, &exclude)?;
+        let expected = if self.expected.try_exists().with_context(|| {
+            format!(
+                "Could not determine whether `{}` exists",
+                self.expected.display()
+            )
+        })? {
+            files(&self.expected, &self.expected, &BTreeSet::new())?
+        } else {
+            BTreeMap::new()
+        };
+
+        if self.bless || env::enabled(env::DYLINT_BLESS) {
+            let blessed = bless(&self.expected, &expected, &actual)?;
+            report_blessed(&blessed);
+            return Ok(());
+        }
+
+        let mismatches = mismatches(&self.expected, &expected, &actual);
+
+        ensure!(
+            mismatches.is_empty(),
+            "Fixed package does not match `{}`:\n\n{}",
+            self.expected.display(),
+            mismatches.join("\n")
+        );
+
+        Ok(())
+    }
+
+    fn fix(&self, package: &Path) -> Result<()> {
+        let library_path = library_path(&self.name)?;
+
+        // smoelius: `cargo fix` refuses to run on a package that is not under version control,
+        // which the temporary copy is not.
+        let mut args = vec![String::from("--allow-no-vcs")];
+        args.extend(self.args.iter().cloned());
+
+        let opts = dylint::opts::Dylint {
+            operation: dylint::opts::Operation::Check(dylint::opts::Check {
+                lib_sel: dylint::opts::LibrarySelection {
+                    lib_paths: vec![library_path.to_string_lossy().into_owned()],
+                    manifest_path: Some(package.join("Cargo.toml").to_string_lossy().into_owned()),
+                    ..Default::default()
+                },
+                fix: true,
+                args,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // smoelius: The `compiletest_rs` backend sets `DYLINT_TOML` for the whole process while it
+        // holds its mutex. Holding the mutex here keeps that setting from reaching `cargo fix`.
+        #[cfg(not(feature = "ui_test"))]
+        let _lock = crate::compiletest_runner::MUTEX
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        dylint::run(&opts).with_context(|| {
+            format!(
+                "Could not apply fixes to a copy of `{}`",
+                self.fixture.display()
+            )
+        })
+    }
+}
+
+/// Returns the contents of the files in `dir` and its subdirectories, keyed by their paths relative
+/// to `root`, and excluding the files and directories in `exclude`
+fn files(
+    root: &Path,
+    dir: &Path,
+    exclude: &BTreeSet<PathBuf>,
+) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut contents = BTreeMap::new();
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        if exclude.contains(&path) {
+            continue;
+        }
+        if path.is_dir() {
+            contents.extend(files(root, &path, exclude)?);
+        } else {
+            let relative = path
+                .strip_prefix(root)
+                .with_context(|| format!("`{}` is not in `{}`", path.display(), root.display()))?;
+            let bytes =
+                read(&path).with_context(|| format!("`read` failed for `{}`", path.display()))?;
+            contents.insert(relative.to_path_buf(), bytes);
+        }
+    }
+
+    Ok(contents)
+}
+
+/// Returns a description of each file that differs between `expected` and `actual`, with a unified
+/// diff for each file in both
+fn mismatches(
+    expected_dir: &Path,
+    expected: &BTreeMap<PathBuf, Vec<u8>>,
+    actual: &BTreeMap<PathBuf, Vec<u8>>,
+) -> Vec<String> {
+    let paths = expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<BTreeSet<_>>();
+
+    paths
+        .into_iter()
+        .filter_map(|path| match (expected.get(path), actual.get(path)) {
+            (Some(expected_contents), Some(actual_contents))
+                if expected_contents != actual_contents =>
+            {
+                Some(unified_diff(
+                    expected_dir,
+                    path,
+                    expected_contents,
+                    actual_contents,
+                ))
+            }
+            (Some(_), None) => Some(format!(
+                "`{}` is missing from the fixed package\n",
+                path.display()
+            )),
+            (None, Some(_)) => Some(format!(
+                "`{}` is in the fixed package, but not in `{}`\n",
+                path.display(),
+                expected_dir.display()
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unified_diff(expected_dir: &Path, path: &Path, expected: &[u8], actual: &[u8]) -> String {
+    let expected = String::from_utf8_lossy(expected);
+    let actual = String::from_utf8_lossy(actual);
+    TextDiff::from_lines(&*expected, &*actual)
+        .unified_diff()
+        .header(
+            &expected_dir.join(path).to_string_lossy(),
+            &Path::new("fixed").join(path).to_string_lossy(),
+        )
+        .to_string()
+}
+
+/// Makes the files in `expected_dir` match `actual`, and returns the paths of the files that
+/// changed
+fn bless(
+    expected_dir: &Path,
+    expected: &BTreeMap<PathBuf, Vec<u8>>,
+    actual: &BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<Vec<PathBuf>> {
+    let mut blessed = Vec::new();
+
+    for path in expected.keys().filter(|path| !actual.contains_key(*path)) {
+        let path = expected_dir.join(path);
+        remove_file(&path)
+            .with_context(|| format!("`remove_file` failed for `{}`", path.display()))?;
+        blessed.push(path);
+    }
+
+    for (path, contents) in actual {
+        if expected.get(path) == Some(contents) {
+            continue;
+        }
+        let path = expected_dir.join(path);
+        let parent = path
+            .parent()
+            .ok_or_else(|| anyhow!("Could not get parent directory of `{}`", path.display()))?;
+        create_dir_all(parent)
+            .with_context(|| format!("`create_dir_all` failed for `{}`", parent.display()))?;
+        write(&path, contents)
+            .with_context(|| format!("`write` failed for `{}`", path.display()))?;
+        blessed.push(path);
+    }
+
+    blessed.sort();
+
+    Ok(blessed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mismatches_include_unified_diffs() {
+        let expected = BTreeMap::from([
+            (PathBuf::from("src/lib.rs"), b"a\nb\nc\n".to_vec()),
+            (PathBuf::from("src/missing.rs"), Vec::new()),
+        ]);
+        let actual = BTreeMap::from([
+            (PathBuf::from("src/extra.rs"), Vec::new()),
+            (PathBuf::from("src/lib.rs"), b"a\nB\nc\n".to_vec()),
+        ]);
+
+        let mismatches = mismatches(Path::new("expected"), &expected, &actual);
+
+        assert_eq!(3, mismatches.len());
+        assert!(mismatches[0].starts_with("`src/extra.rs` is in the fixed package"));
+        assert!(mismatches[1].contains("\n-b\n+B\n"));
+        assert!(mismatches[2].starts_with("`src/missing.rs` is missing"));
+    }
+}
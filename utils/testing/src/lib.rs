@@ -38,10 +38,55 @@
 //!
 //! A `Test` instance has the following methods:
 //!
+//! - `bless` - overwrite the expected output files with the actual output (see below)
 //! - `dylint_toml` - set the `dylint.toml` file's contents (for testing [configurable libraries])
 //! - `rustc_flags` - pass flags to the compiler when running the test
+//! - `rustfix` - check the library's machine-applicable suggestions (see below)
 //! - `run` - run the test
 //!
+//! # Per-file configurations
+//!
+//! `Test::dylint_toml` sets one `dylint.toml` configuration for all of a test's files. A file can
+//! instead specify its own configuration with one or more header lines:
+//!
+//! ```rust,ignore
+//! //@ dylint-toml: non_local_effect_before_error_return.public_only = true
+//! ```
+//!
+//! Multiple header lines are joined with newlines. Alternatively, the configuration can go in a
+//! sibling file with the extension `.dylint.toml` (e.g., `main.dylint.toml` for `main.rs`). A
+//! file's own configuration takes precedence over the one set with `Test::dylint_toml`. Note that a
+//! header line shifts the line numbers in the file's `.stderr` file.
+//!
+//! Keys must be qualified with a library name, as above. An unqualified key causes the test to
+//! fail, because no library would read it.
+//!
+//! # Checking suggestions
+//!
+//! `Test::rustfix` applies every `MachineApplicable` suggestion the library emits for a `.rs` file
+//! and compares the result to a sibling `.fixed` file. It then compiles the fixed code and fails if
+//! any new errors appear. This is similar to Clippy's rustfix testing. Files with a `run-rustfix`
+//! header are skipped, because `compiletest_rs` already checks them.
+//!
+//! # Testing fixes on packages
+//!
+//! [`fix::Test`] tests a library's fixes on a whole package, rather than on individual `.rs` files.
+//! It copies a fixture package to a temporary directory, runs `cargo dylint --fix` on the copy with
+//! the library, and compares the resulting package to an expected snapshot directory. Differences
+//! are reported as unified diffs. For example:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn fix() {
+//!     dylint_testing::fix::Test::new(env!("CARGO_PKG_NAME"), "fix/fixture", "fix/expected").run();
+//! }
+//! ```
+//!
+//! The fixture's `Cargo.toml` should contain an empty `[workspace]` table, and the fixture can
+//! include a `dylint.toml` file to configure the library. The `target` directory is not compared,
+//! and neither is `Cargo.lock` unless the fixture contains one. Blessing (see below) overwrites the
+//! expected snapshot directory. For an example, see [`const_path_join`] in this repository.
+//!
 //! # Updating `.stderr` files
 //!
 //! If the standard error that results from running your `.rs` file differs from the contents of
@@ -91,10 +136,44 @@
 //! report should contain a line of the form `Actual stderr saved to PATH`. Copying `PATH` to your
 //! `.stderr` file should update it completely.
 //!
+//! Alternatively, set the `DYLINT_BLESS` environment variable when running your tests, e.g.:
+//!
+//! ```sh
+//! DYLINT_BLESS=1 cargo test
+//! ```
+//!
+//! In this "bless" mode, `dylint_testing` overwrites your `.stderr` files, and your `.fixed` files
+//! where present, with the actual output rather than reporting differences. When the tests finish,
+//! the files that changed are listed. Calling `bless` on a [`ui::Test`] has the same effect.
+//!
 //! Additional documentation on `compiletest_rs` can be found in [its repository].
 //!
+//! # `ui_test` feature
+//!
+//! By default, tests are run with `compiletest_rs`. Because `compiletest_rs` runs tests in the
+//! current process's environment, `dylint_testing` runs them one at a time. Enabling the `ui_test`
+//! feature runs tests with the [`ui_test` crate] instead. With this feature:
+//!
+//! - environment variables such as `DYLINT_TOML` and `DYLINT_LIBS` are passed to each test, and
+//!   tests run in parallel
+//! - inline `//~` annotations are checked when present
+//! - a `//@rustc-flags: ...` header passes flags to the compiler for one file
+//!
+//! The `ui::Test` API is the same with either backend. However, `compiletest_rs` headers such as
+//! `// run-rustfix` are not recognized by `ui_test`; use `Test::rustfix` instead.
+//!
+//! `compiletest_rs` is a default feature. To avoid building it when using the `ui_test` feature,
+//! disable default features:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! dylint_testing = { version = "5.0.0", default-features = false, features = ["ui_test"] }
+//! ```
+//!
 //! [Dylint]: https://github.com/trailofbits/dylint/tree/master
 //! [`compiletest_rs`]: https://github.com/Manishearth/compiletest-rs
+//! [`const_path_join`]: https://github.com/trailofbits/dylint/tree/master/examples/restriction/const_path_join/src/lib.rs
+//! [`fix::Test`]: https://docs.rs/dylint_testing/latest/dylint_testing/fix/struct.Test.html
 //! [`non_thread_safe_call_in_test`]: https://github.com/trailofbits/dylint/tree/master/examples/general/non_thread_safe_call_in_test/src/lib.rs
 //! [`question_mark_in_expression`]: https://github.com/trailofbits/dylint/tree/master/examples/restriction/question_mark_in_expression/Cargo.toml
 //! [`ui::Test::example`]: https://docs.rs/dylint_testing/latest/dylint_testing/ui/struct.Test.html#method.example
@@ -103,6 +182,7 @@
 //! [`ui::Test`]: https://docs.rs/dylint_testing/latest/dylint_testing/ui/struct.Test.html
 //! [`ui_test_example`]: https://docs.rs/dylint_testing/latest/dylint_testing/fn.ui_test_example.html
 //! [`ui_test_examples`]: https://docs.rs/dylint_testing/latest/dylint_testing/fn.ui_test_examples.html
+//! [`ui_test` crate]: https://github.com/oli-obk/ui_test
 //! [`ui_test`]: https://docs.rs/dylint_testing/latest/dylint_testing/fn.ui_test.html
 //! [configurable libraries]: https://github.com/trailofbits/dylint/tree/master#configurable-libraries
 //! [docs.rs documentation]: https://docs.rs/dylint_testing/latest/dylint_testing/
@@ -111,24 +191,42 @@
 
 use anyhow::{Context, Result, anyhow, ensure};
 use cargo_metadata::{Metadata, Package, Target, TargetKind};
-use compiletest_rs as compiletest;
 use dylint_internal::{CommandExt, env, library_filename, rustup::is_rustc};
 use once_cell::sync::OnceCell;
 use regex::Regex;
 use std::{
-    env::{consts, remove_var, set_var, var_os},
-    ffi::{OsStr, OsString},
-    fs::{copy, read_dir, remove_file},
+    collections::{BTreeMap, BTreeSet},
+    env::{consts, set_var},
+    ffi::OsStr,
+    fs::{copy, create_dir, read, read_dir, remove_file},
     io::BufRead,
     path::{Path, PathBuf},
-    sync::{LazyLock, Mutex},
+    sync::LazyLock,
 };
 
+#[cfg(not(any(feature = "compiletest_rs", feature = "ui_test")))]
+compile_error!("either the `compiletest_rs` or the `ui_test` feature must be enabled");
+
+#[cfg(not(feature = "ui_test"))]
+mod compiletest_runner;
+
+mod dylint_toml;
+
+mod suggestions;
+
+#[cfg(feature = "ui_test")]
+mod ui_test_runner;
+
+pub mod fix;
+
 pub mod ui;
 
 static DRIVER: OnceCell<Result<PathBuf>> = OnceCell::new();
 static LINKING_FLAGS: OnceCell<Vec<String>> = OnceCell::new();
 
+/// The extensions of the files that hold a test's expected output
+const EXPECTED_OUTPUT_EXTENSIONS: [&str; 3] = ["fixed", "stderr", "stdout"];
+
 /// Test a library on all source files in a directory.
 ///
 /// - `name` is the name of a Dylint library to be tested. (Often, this is the same as the package
@@ -193,12 +291,20 @@ fn initialize(name: &str) -> &Result<PathBuf> {
 
 #[doc(hidden)]
 pub fn dylint_libs(name: &str) -> Result<String> {
+    let paths = vec![library_path(name)?];
+    serde_json::to_string(&paths).map_err(Into::into)
+}
+
+/// Returns the path of the library `name` built by [`initialize`]
+fn library_path(name: &str) -> Result<PathBuf> {
     let metadata = dylint_internal::cargo::current_metadata().unwrap();
     let rustup_toolchain = env::var(env::RUSTUP_TOOLCHAIN)?;
     let filename = library_filename(name, &rustup_toolchain);
-    let path = metadata.target_directory.join("debug").join(filename);
-    let paths = vec![path];
-    serde_json::to_string(&paths).map_err(Into::into)
+    Ok(metadata
+        .target_directory
+        .join("debug")
+        .join(filename)
+        .into_std_path_buf())
 }
 
 fn example_target(package: &Package, example: &str) -> Result<Target> {
@@ -220,13 +326,14 @@ fn example_targets(package: &Package) -> Result<Vec<Target>> {
         .collect())
 }
 
+/// Runs the test on one example target and returns the expected output files that were blessed
 fn run_example_test(
     driver: &Path,
     metadata: &Metadata,
     package: &Package,
     target: &Target,
     config: &ui::Config,
-) -> Result<()> {
+) -> Result<Vec<PathBuf>> {
     let linking_flags = linking_flags(metadata, package, target)?;
     let file_name = target
         .src_path
@@ -244,7 +351,7 @@ fn run_example_test(
             to.to_string_lossy()
         )
     })?;
-    for extension in ["fixed", "stderr", "stdout"] {
+    for extension in EXPECTED_OUTPUT_EXTENSIONS {
         copy_with_extension(&target.src_path, &to, extension)
             .map(|_| ())
             .unwrap_or_default();
@@ -252,10 +359,19 @@ fn run_example_test(
 
     let mut config = config.clone();
     config.rustc_flags.extend(linking_flags.iter().cloned());
+    if let Some(dylint_toml) = dylint_toml::per_file(target.src_path.as_std_path())? {
+        config.dylint_toml = Some(dylint_toml);
+    }
 
-    run_tests(driver, src_base, &config);
+    let blessed = run_tests(driver, src_base, &config)?;
 
-    Ok(())
+    // smoelius: The test ran on a copy of the example. So blessed files must be copied back to the
+    // example's directory.
+    let dir = target
+        .src_path
+        .parent()
+        .ok_or_else(|| anyhow!("Could not get parent directory of `{}`", target.src_path))?;
+    restore_blessed(blessed, src_base, dir.as_std_path())
 }
 
 fn linking_flags(
@@ -408,75 +524,269 @@ fn copy_with_extension<P: AsRef<Path>, Q: AsRef<Path>>(
     copy(from, to).map_err(Into::into)
 }
 
-static MUTEX: Mutex<()> = Mutex::new(());
-
-fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config) {
-    let _lock = MUTEX.lock().unwrap();
-
-    // smoelius: There doesn't seem to be a way to set environment variables using `compiletest`'s
-    // [`Config`](https://docs.rs/compiletest_rs/0.7.1/compiletest_rs/common/struct.Config.html)
-    // struct. For comparison, where Clippy uses `compiletest`, it sets environment variables
-    // directly (see: https://github.com/rust-lang/rust-clippy/blob/master/tests/compile-test.rs).
-    //
-    // Of course, even if `compiletest` had such support, it would need to be incorporated into
-    // `dylint_testing`.
-
-    let _var = config
-        .dylint_toml
-        .as_ref()
-        .map(|value| VarGuard::set(env::DYLINT_TOML, value));
-
-    let config = compiletest::Config {
-        mode: compiletest::common::Mode::Ui,
-        rustc_path: driver.to_path_buf(),
-        src_base: src_base.to_path_buf(),
-        target_rustcflags: Some(
-            config.rustc_flags.clone().join(" ")
-                + " --emit=metadata"
-                + if cfg!(feature = "deny_warnings") {
-                    " -Dwarnings"
-                } else {
-                    ""
-                }
-                + " -Zui-testing",
-        ),
-        ..compiletest::Config::default()
+/// Runs the tests in `src_base` and, if blessing, returns the expected output files that changed.
+/// Files with their own `dylint.toml` configurations (see the `dylint_toml` module) are run
+/// separately, one run per configuration.
+fn run_src_base(driver: &Path, src_base: &Path, config: &ui::Config) -> Result<Vec<PathBuf>> {
+    let mut groups = BTreeMap::<String, Vec<PathBuf>>::new();
+    for path in top_level_source_files(src_base)? {
+        if let Some(dylint_toml) = dylint_toml::per_file(&path)? {
+            groups.entry(dylint_toml).or_default().push(path);
+        }
+    }
+
+    if groups.is_empty() {
+        return run_tests(driver, src_base, config);
+    }
+
+    // smoelius: A configuration applies to a whole run of the test runner. So the remaining files
+    // are run from a copy of `src_base` without the configured files, and each group of configured
+    // files is run from a copy of just those files.
+    let mut exclude = BTreeSet::new();
+    for path in groups.values().flatten() {
+        exclude.insert(path.clone());
+        for extension in EXPECTED_OUTPUT_EXTENSIONS {
+            exclude.insert(path.with_extension(extension));
+        }
+    }
+
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+    copy_dir(src_base, tempdir.path(), &exclude)?;
+    let blessed = run_tests(driver, tempdir.path(), config)?;
+    let mut blessed = restore_blessed(blessed, tempdir.path(), src_base)?;
+
+    for (dylint_toml, paths) in groups {
+        let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+        for path in &paths {
+            let file_name = path
+                .file_name()
+                .ok_or_else(|| anyhow!("Could not get file name of `{}`", path.display()))?;
+            let to = tempdir.path().join(file_name);
+            copy(path, &to).with_context(|| {
+                format!("Could not copy `{}` to `{}`", path.display(), to.display())
+            })?;
+            for extension in EXPECTED_OUTPUT_EXTENSIONS {
+                copy_with_extension(path, &to, extension)
+                    .map(|_| ())
+                    .unwrap_or_default();
+            }
+        }
+
+        let mut config = config.clone();
+        config.dylint_toml = Some(dylint_toml);
+
+        let group_blessed = run_tests(driver, tempdir.path(), &config)?;
+        blessed.extend(restore_blessed(group_blessed, tempdir.path(), src_base)?);
+    }
+
+    blessed.sort();
+
+    Ok(blessed)
+}
+
+/// Runs the tests in `src_base` and, if blessing, returns the expected output files that changed
+fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config) -> Result<Vec<PathBuf>> {
+    let bless = config.bless();
+
+    let before = if bless {
+        expected_outputs(src_base)?
+    } else {
+        BTreeMap::new()
     };
 
-    compiletest::run_tests(&config);
+    #[cfg(not(feature = "ui_test"))]
+    compiletest_runner::run_tests(driver, src_base, config, bless);
+
+    #[cfg(feature = "ui_test")]
+    ui_test_runner::run_tests(driver, src_base, config, bless)?;
+
+    if config.rustfix {
+        let mut rustc_flags = config.rustc_flags.clone();
+        if cfg!(feature = "deny_warnings") {
+            rustc_flags.push(String::from("-Dwarnings"));
+        }
+        suggestions::check(
+            driver,
+            src_base,
+            &rustc_flags,
+            config.dylint_toml.as_deref(),
+            bless,
+        )?;
+    }
+
+    if !bless {
+        return Ok(Vec::new());
+    }
+
+    let mut after = expected_outputs(src_base)?;
+
+    let mut blessed = before
+        .into_iter()
+        .filter_map(|(path, contents)| {
+            let new_contents = after.remove(&path);
+            (new_contents.as_ref() != Some(&contents)).then_some(path)
+        })
+        .collect::<Vec<_>>();
+    blessed.extend(after.into_keys());
+    blessed.sort();
+
+    Ok(blessed)
 }
 
-// smoelius: `VarGuard` was copied from:
-// https://github.com/rust-lang/rust-clippy/blob/9cc8da222b3893bc13bc13c8827e93f8ea246854/tests/compile-test.rs
-// smoelius: Clippy dropped `VarGuard` when it switched to `ui_test`:
-// https://github.com/rust-lang/rust-clippy/commit/77d10ac63dae6ef0a691d9acd63d65de9b9bf88e
+/// The flags passed to the driver for each test, in addition to those `compiletest_rs` or
+/// `ui_test` pass
+fn test_rustc_flags(config: &ui::Config) -> Vec<String> {
+    let mut rustc_flags = config.rustc_flags.clone();
+    rustc_flags.push(String::from("--emit=metadata"));
+    if cfg!(feature = "deny_warnings") {
+        rustc_flags.push(String::from("-Dwarnings"));
+    }
+    rustc_flags.push(String::from("-Zui-testing"));
+    rustc_flags
+}
 
-/// Restores an env var on drop
-#[must_use]
-struct VarGuard {
-    key: &'static str,
-    value: Option<OsString>,
+/// Copies blessed files from `tempdir`, where a copy of the tests ran, back to `dir`, and returns
+/// the paths of the copied files within `dir`
+fn restore_blessed(blessed: Vec<PathBuf>, tempdir: &Path, dir: &Path) -> Result<Vec<PathBuf>> {
+    blessed
+        .into_iter()
+        .map(|path| {
+            let relative = path.strip_prefix(tempdir).with_context(|| {
+                format!("`{}` is not in `{}`", path.display(), tempdir.display())
+            })?;
+            let dest = dir.join(relative);
+            if path.try_exists().with_context(|| {
+                format!("Could not determine whether `{}` exists", path.display())
+            })? {
+                copy(&path, &dest).with_context(|| {
+                    format!(
+                        "Could not copy `{}` to `{}`",
+                        path.display(),
+                        dest.display()
+                    )
+                })?;
+            } else {
+                remove_file(&dest)
+                    .with_context(|| format!("`remove_file` failed for `{}`", dest.display()))?;
+            }
+            Ok(dest)
+        })
+        .collect()
 }
 
-impl VarGuard {
-    fn set(key: &'static str, val: impl AsRef<OsStr>) -> Self {
-        let value = var_os(key);
-        unsafe {
-            set_var(key, val);
+/// Copies `from` to `to` recursively, excluding the files and directories in `exclude`
+fn copy_dir(from: &Path, to: &Path, exclude: &BTreeSet<PathBuf>) -> Result<()> {
+    for entry in
+        read_dir(from).with_context(|| format!("`read_dir` failed for `{}`", from.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", from.display()))?;
+        let path = entry.path();
+        let dest = to.join(entry.file_name());
+        if exclude.contains(&path) {
+            continue;
+        }
+        if path.is_dir() {
+            create_dir(&dest)
+                .with_context(|| format!("`create_dir` failed for `{}`", dest.display()))?;
+            copy_dir(&path, &dest, exclude)?;
+        } else {
+            copy(&path, &dest).with_context(|| {
+                format!(
+                    "Could not copy `{}` to `{}`",
+                    path.display(),
+                    dest.display()
+                )
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the `.rs` files directly in `dir`
+fn top_level_source_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        if path.is_file() && path.extension().is_some_and(|extension| extension == "rs") {
+            paths.push(path);
         }
-        Self { key, value }
     }
+
+    paths.sort();
+
+    Ok(paths)
 }
 
-impl Drop for VarGuard {
-    fn drop(&mut self) {
-        match self.value.as_deref() {
-            None => unsafe { remove_var(self.key) },
-            Some(value) => unsafe { set_var(self.key, value) },
+/// Returns the contents of the expected output files in `dir` and its subdirectories
+fn expected_outputs(dir: &Path) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut outputs = BTreeMap::new();
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        if path.is_dir() {
+            outputs.extend(expected_outputs(&path)?);
+        } else if path
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|extension| EXPECTED_OUTPUT_EXTENSIONS.contains(&extension))
+        {
+            let contents =
+                read(&path).with_context(|| format!("`read` failed for `{}`", path.display()))?;
+            outputs.insert(path, contents);
         }
     }
+
+    Ok(outputs)
+}
+
+/// Prints the expected output files that were blessed
+fn report_blessed(blessed: &[PathBuf]) {
+    if blessed.is_empty() {
+        eprintln!("No expected output files changed");
+        return;
+    }
+    eprintln!("Blessed {} expected output file(s):", blessed.len());
+    for path in blessed {
+        eprintln!("    {}", path.display());
+    }
 }
 
 fn snake_case(name: &str) -> String {
     name.replace('-', "_")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::{create_dir, write};
+
+    #[test]
+    fn expected_outputs_are_found_recursively() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let src_base = tempdir.path();
+        create_dir(src_base.join("dir")).unwrap();
+        for file_name in ["main.rs", "main.stderr", "dir/other.fixed", "dir/other.txt"] {
+            write(src_base.join(file_name), file_name).unwrap();
+        }
+
+        let expected_outputs = expected_outputs(src_base).unwrap();
+
+        assert_eq!(
+            [
+                src_base.join("dir/other.fixed"),
+                src_base.join("main.stderr")
+            ]
+            .iter()
+            .collect::<Vec<_>>(),
+            expected_outputs.keys().collect::<Vec<_>>()
+        );
+    }
+}
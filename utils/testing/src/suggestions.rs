@@ -0,0 +1,206 @@
+//! Checks that applying a library's `MachineApplicable` suggestions to a test's `.rs` file produces
+//! the test's `.fixed` file, and that the fixed code compiles without new errors. This is similar
+//! to Clippy's rustfix testing.
+//!
+//! Files with a `run-rustfix` header are skipped, because `compiletest_rs` checks them itself.
+
+use anyhow::{Context, Result, anyhow, ensure};
+use dylint_internal::{CommandExt, env};
+use rustfix::{Filter, apply_suggestions, get_suggestions_from_json};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs::{create_dir, read_dir, read_to_string, write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+pub fn check(
+    driver: &Path,
+    src_base: &Path,
+    rustc_flags: &[String],
+    dylint_toml: Option<&str>,
+    bless: bool,
+) -> Result<()> {
+    let failures = source_files(src_base)?
+        .into_iter()
+        .filter_map(|path| {
+            check_file(driver, &path, rustc_flags, dylint_toml, bless)
+                .err()
+                .map(|error| format!("{error:?}"))
+        })
+        .collect::<Vec<_>>();
+
+    ensure!(
+        failures.is_empty(),
+        "Machine-applicable suggestions check failed:\n\n{}",
+        failures.join("\n\n")
+    );
+
+    Ok(())
+}
+
+fn check_file(
+    driver: &Path,
+    path: &Path,
+    rustc_flags: &[String],
+    dylint_toml: Option<&str>,
+    bless: bool,
+) -> Result<()> {
+    let source = read_to_string(path)
+        .with_context(|| format!("`read_to_string` failed for `{}`", path.display()))?;
+
+    if source
+        .lines()
+        .take_while(|line| line.starts_with("//"))
+        .any(|line| {
+            line.trim_start_matches(['/', '@', ' '])
+                .starts_with("run-rustfix")
+        })
+    {
+        return Ok(());
+    }
+
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+
+    let stderr = compile(driver, path, rustc_flags, dylint_toml, tempdir.path())?;
+    let suggestions =
+        get_suggestions_from_json(&stderr, &HashSet::new(), Filter::MachineApplicableOnly)
+            .with_context(|| format!("Could not parse suggestions for `{}`", path.display()))?;
+
+    let fixed_path = path.with_extension("fixed");
+    let fixed_path_exists = fixed_path.try_exists().with_context(|| {
+        format!(
+            "Could not determine whether `{}` exists",
+            fixed_path.display()
+        )
+    })?;
+
+    if suggestions.is_empty() && !fixed_path_exists {
+        return Ok(());
+    }
+
+    let fixed = apply_suggestions(&source, &suggestions)
+        .with_context(|| format!("Could not apply suggestions to `{}`", path.display()))?;
+
+    if bless {
+        write(&fixed_path, &fixed)
+            .with_context(|| format!("`write` failed for `{}`", fixed_path.display()))?;
+    } else {
+        ensure!(
+            fixed_path_exists,
+            "`{}` has machine-applicable suggestions, but `{}` does not exist",
+            path.display(),
+            fixed_path.display()
+        );
+        let expected = read_to_string(&fixed_path)
+            .with_context(|| format!("`read_to_string` failed for `{}`", fixed_path.display()))?;
+        ensure!(
+            fixed == expected,
+            "Applying the machine-applicable suggestions to `{}` does not produce `{}`. The actual \
+             fixed code is:\n{fixed}",
+            path.display(),
+            fixed_path.display()
+        );
+    }
+
+    // smoelius: Compile the fixed code under the original file name so that the crate name, and
+    // thus the diagnostics, are the same.
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Could not get file name of `{}`", path.display()))?;
+    let fixed_dir = tempdir.path().join("fixed");
+    let fixed_rs = fixed_dir.join(file_name);
+    create_dir(&fixed_dir)
+        .with_context(|| format!("`create_dir` failed for `{}`", fixed_dir.display()))?;
+    write(&fixed_rs, &fixed)
+        .with_context(|| format!("`write` failed for `{}`", fixed_rs.display()))?;
+
+    let fixed_stderr = compile(driver, &fixed_rs, rustc_flags, dylint_toml, tempdir.path())?;
+
+    let original_errors = errors(&stderr);
+    let new_errors = errors(&fixed_stderr)
+        .into_iter()
+        .filter(|error| !original_errors.contains(error))
+        .collect::<Vec<_>>();
+    ensure!(
+        new_errors.is_empty(),
+        "The fixed code of `{}` has new errors:\n{}",
+        path.display(),
+        new_errors.join("\n")
+    );
+
+    Ok(())
+}
+
+/// Runs the driver on `path` and returns the JSON diagnostics written to standard error
+fn compile(
+    driver: &Path,
+    path: &Path,
+    rustc_flags: &[String],
+    dylint_toml: Option<&str>,
+    out_dir: &Path,
+) -> Result<String> {
+    let mut command = Command::new(driver);
+    if let Some(dylint_toml) = dylint_toml {
+        command.env(env::DYLINT_TOML, dylint_toml);
+    }
+    let output = command
+        .arg(path)
+        .args(rustc_flags)
+        .args(["--error-format=json", "--emit=metadata", "--out-dir"])
+        .arg(out_dir)
+        .logged_output(false)?;
+    String::from_utf8(output.stderr).map_err(Into::into)
+}
+
+/// Returns the messages of the errors in `stderr`, excluding the final "aborting due to" message
+fn errors(stderr: &str) -> BTreeSet<String> {
+    stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|diagnostic| diagnostic["level"] == "error")
+        .filter_map(|diagnostic| diagnostic["message"].as_str().map(ToOwned::to_owned))
+        .filter(|message| !message.starts_with("aborting due to"))
+        .collect()
+}
+
+/// Returns the `.rs` files in `dir` and its subdirectories, excluding `auxiliary` directories
+fn source_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for entry in
+        read_dir(dir).with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?
+    {
+        let entry = entry.with_context(|| format!("`read_dir` failed for `{}`", dir.display()))?;
+        let path = entry.path();
+        if path.is_dir() {
+            if entry.file_name() != "auxiliary" {
+                paths.extend(source_files(&path)?);
+            }
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn errors_exclude_warnings_and_aborting() {
+        let stderr = r#"{"level":"warning","message":"unused variable: `x`"}
+{"level":"error","message":"mismatched types"}
+not json
+{"level":"error","message":"aborting due to 1 previous error"}
+"#;
+        assert_eq!(
+            BTreeSet::from([String::from("mismatched types")]),
+            errors(stderr)
+        );
+    }
+}
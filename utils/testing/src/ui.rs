@@ -1,4 +1,7 @@
-use crate::{example_target, example_targets, initialize, run_example_test, run_tests};
+use crate::{
+    example_target, example_targets, initialize, report_blessed, run_example_test, run_src_base,
+};
+use dylint_internal::env;
 use std::{
     env::current_dir,
     path::{Path, PathBuf},
@@ -14,6 +17,14 @@ enum Target {
 pub(super) struct Config {
     pub(super) rustc_flags: Vec<String>,
     pub(super) dylint_toml: Option<String>,
+    pub(super) bless: bool,
+    pub(super) rustfix: bool,
+}
+
+impl Config {
+    pub(super) fn bless(&self) -> bool {
+        self.bless || env::enabled(env::DYLINT_BLESS)
+    }
 }
 
 /// Test builder
@@ -65,6 +76,22 @@ impl Test {
         self
     }
 
+    /// Overwrite the expected output files (`.stderr`, `.fixed`, etc.) with the actual output,
+    /// rather than failing when they differ. Setting the `DYLINT_BLESS` environment variable to a
+    /// non-zero value has the same effect.
+    pub const fn bless(&mut self) -> &mut Self {
+        self.config.bless = true;
+        self
+    }
+
+    /// Apply the `MachineApplicable` suggestions that the library emits for each `.rs` file, check
+    /// that the result matches the file's `.fixed` file, and check that the fixed code compiles
+    /// without new errors. Files with a `run-rustfix` header are left to `compiletest_rs`.
+    pub const fn rustfix(&mut self) -> &mut Self {
+        self.config.rustfix = true;
+        self
+    }
+
     /// Run the test.
     #[allow(clippy::needless_pass_by_ref_mut)]
     pub fn run(&mut self) {
@@ -82,10 +109,8 @@ impl Test {
     fn run_immutable(&self) {
         let driver = initialize(&self.name).as_ref().unwrap();
 
-        match &self.target {
-            Target::SrcBase(src_base) => {
-                run_tests(driver, src_base, &self.config);
-            }
+        let blessed = match &self.target {
+            Target::SrcBase(src_base) => run_src_base(driver, src_base, &self.config).unwrap(),
             Target::Example(example) => {
                 let metadata = dylint_internal::cargo::current_metadata().unwrap();
                 let current_dir = current_dir().unwrap();
@@ -93,7 +118,7 @@ impl Test {
                     dylint_internal::cargo::package_with_root(&metadata, &current_dir).unwrap();
                 let target = example_target(&package, example).unwrap();
 
-                run_example_test(driver, &metadata, &package, &target, &self.config).unwrap();
+                run_example_test(driver, &metadata, &package, &target, &self.config).unwrap()
             }
             Target::Examples => {
                 let metadata = dylint_internal::cargo::current_metadata().unwrap();
@@ -102,10 +127,17 @@ impl Test {
                     dylint_internal::cargo::package_with_root(&metadata, &current_dir).unwrap();
                 let targets = example_targets(&package).unwrap();
 
-                for target in targets {
-                    run_example_test(driver, &metadata, &package, &target, &self.config).unwrap();
-                }
+                targets
+                    .iter()
+                    .flat_map(|target| {
+                        run_example_test(driver, &metadata, &package, target, &self.config).unwrap()
+                    })
+                    .collect()
             }
+        };
+
+        if self.config.bless() {
+            report_blessed(&blessed);
         }
     }
 }
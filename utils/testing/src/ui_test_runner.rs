@@ -0,0 +1,83 @@
+//! An alternative backend, based on [`ui_test`] and enabled by the `ui_test` feature. Environment
+//! variables like `DYLINT_TOML` are passed to each test's driver invocation rather than set for the
+//! whole process. So tests are run in parallel.
+//!
+//! In addition to `.stderr` files, the backend supports:
+//!
+//! - inline `//~` annotations, which are checked when present but not required
+//! - `//@rustc-flags: ...` headers, which pass flags to the driver for one file (an alias for
+//!   `ui_test`'s `//@compile-flags: ...`)
+//!
+//! [`ui_test`]: https://github.com/oli-obk/ui_test
+
+use crate::{test_rustc_flags, ui};
+use anyhow::{Context, Result, anyhow};
+use dylint_internal::env;
+use std::{env::var_os, ffi::OsString, path::Path};
+use ui_test::{
+    Config, bless_output_files, default_file_filter, default_per_file_config,
+    error_on_output_conflict, run_tests_generic, spanned::Spanned, status_emitter,
+};
+
+pub fn run_tests(driver: &Path, src_base: &Path, config: &ui::Config, bless: bool) -> Result<()> {
+    // smoelius: Give each run its own output directory. Tests in different `src_base` directories
+    // can have the same file names, and those tests can run at the same time.
+    let tempdir = tempfile::tempdir().with_context(|| "`tempdir` failed")?;
+
+    let mut ui_test_config = Config {
+        out_dir: tempdir.path().to_path_buf(),
+        output_conflict_handling: if bless {
+            bless_output_files
+        } else {
+            error_on_output_conflict
+        },
+        bless_command: Some(format!("{}=1 cargo test", env::DYLINT_BLESS)),
+        ..Config::rustc(src_base)
+    };
+
+    ui_test_config.program.program = driver.to_path_buf();
+    ui_test_config
+        .program
+        .args
+        .extend(test_rustc_flags(config).into_iter().map(OsString::from));
+
+    let envs = &mut ui_test_config.program.envs;
+    envs.push((
+        env::CLIPPY_DISABLE_DOCS_LINKS.into(),
+        Some(OsString::from("true")),
+    ));
+    envs.push((env::DYLINT_LIBS.into(), var_os(env::DYLINT_LIBS)));
+    if let Some(dylint_toml) = &config.dylint_toml {
+        envs.push((env::DYLINT_TOML.into(), Some(dylint_toml.into())));
+    }
+
+    // smoelius: Match the `compiletest_rs` backend so that existing tests pass unchanged: do not
+    // require annotations, do not check the exit status, do not pass an edition (examples' linking
+    // flags include one), and do not apply suggestions (`Test::rustfix` does that).
+    let defaults = ui_test_config.comment_defaults.base();
+    defaults.require_annotations = Spanned::dummy(false).into();
+    defaults.exit_status = None.into();
+    defaults.custom.remove("edition");
+    defaults.custom.remove("rustfix");
+
+    ui_test_config
+        .custom_comments
+        .insert("rustc-flags", |parser, args, _span| {
+            parser
+                .compile_flags
+                .extend(args.split_whitespace().map(ToOwned::to_owned));
+        });
+
+    // smoelius: `dylint_toml.rs` handles `//@ dylint-toml: ...` headers before the tests are run.
+    ui_test_config
+        .custom_comments
+        .insert("dylint-toml", |_parser, _args, _span| {});
+
+    run_tests_generic(
+        vec![ui_test_config],
+        default_file_filter,
+        default_per_file_config,
+        status_emitter::Text::verbose(),
+    )
+    .map_err(|error| anyhow!("{error:?}"))
+}